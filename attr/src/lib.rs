@@ -0,0 +1,437 @@
+//! Provides `#[dont_panic]` attribute, which ensures the annotated function can't panic.
+//!
+//! The body of the function is wrapped in `dont_panic::call()`, so if the compiler can't prove the
+//! function doesn't panic, you get a linking error. In `panic` mode of `dont_panic` (see its
+//! documentation), the function just runs normally.
+//!
+//! Your crate needs to depend on `dont_panic` as well, since the generated code calls into it.
+//!
+//! # Example
+//!
+//! ```no_compile
+//! extern crate dont_panic;
+//! extern crate dont_panic_attr;
+//!
+//! #[dont_panic_attr::dont_panic]
+//! fn sum(arr: &[u32; 3]) -> u32 {
+//!     arr[0] + arr[1] + arr[2]
+//! }
+//! ```
+//!
+//! The attribute has the same name as the `dont_panic!()` macro. Importing both with
+//! `#[macro_use]` is fine (attributes and bang macros live in different namespaces), but writing
+//! the full path `#[dont_panic_attr::dont_panic]` is clearer.
+//!
+//! # Impl blocks and modules
+//!
+//! The attribute can be used on an `impl` block or an inline module too, guarding every function
+//! in it, including the ones in nested impl blocks, default methods of traits and nested inline
+//! modules. Functions (and other items) can be excluded using `#[dont_panic(skip)]`:
+//!
+//! ```no_compile
+//! #[dont_panic_attr::dont_panic]
+//! mod parser {
+//!     pub fn first(arr: &[u8; 4]) -> u8 {
+//!         arr[0]
+//!     }
+//!
+//!     #[dont_panic(skip)]
+//!     pub fn nth(arr: &[u8], n: usize) -> u8 {
+//!         arr[n]
+//!     }
+//! }
+//! ```
+//!
+//! `const fn`s can't be guarded, so they are skipped, just like functions generated by macros.
+//!
+//! # Checked arithmetic
+//!
+//! `dp_arith!()` rewrites every arithmetic operator (`+`, `-`, `*`, `/`, `%`, `<<`, `>>` and unary
+//! `-`) in the given expression to the corresponding checked operation, calling `dont_panic!()` if
+//! it fails. Every operator gets its own `dont_panic!()` call, so the linking error points at the
+//! operator which may overflow (`dont_panic_overflow$...`) or divide by zero
+//! (`dont_panic_div_by_zero$...`). In `panic` mode, the panic message contains the failing
+//! operation.
+//!
+//! ```no_compile
+//! #[macro_use]
+//! extern crate dont_panic;
+//! extern crate dont_panic_attr;
+//!
+//! use dont_panic_attr::dp_arith;
+//!
+//! fn area(width: u32, height: u32) -> u32 {
+//!     dp_assert!(width <= 1024 && height <= 1024);
+//!     dp_arith!(width * height + 2 * (width + height))
+//! }
+//! ```
+//!
+//! Both operands have to be of the same primitive integer type (the right hand side of shifts can
+//! be any integer type). Operators inside macro invocations are not rewritten and compound
+//! assignments (e.g. `+=`) are not supported.
+//!
+//! # Async functions
+//!
+//! `async fn`s are supported, but only the code of the function itself is checked, the futures it
+//! awaits aren't. (Code generated for every future contains a panic for the case it's polled after
+//! completion, so checking them would always fail.) Annotate the awaited functions too.
+//!
+//! The checking is suspended around every `.await`, `?` and `return` in the body, which have to be
+//! written directly in the function (not inside macro invocations) to be recognized.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenTree};
+use syn::{Attribute, BinOp, Block, Error, Expr, ImplItem, Item, ItemImpl, ItemMod, ItemTrait, Meta, ReturnType, Signature, TraitItem, Type, UnOp};
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+
+/// Ensures the function can't panic by wrapping its body in `dont_panic::call()`.
+///
+/// When used on an `impl` block or an inline module, all functions inside it are guarded, except
+/// for the ones marked `#[dont_panic(skip)]`.
+#[proc_macro_attribute]
+pub fn dont_panic(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    if let Some(token) = attr.clone().into_iter().next() {
+        let message = if is_skip(&attr) {
+            "#[dont_panic(skip)] can only be used inside #[dont_panic] impl block or module"
+        } else {
+            "#[dont_panic] doesn't accept arguments"
+        };
+        return Error::new_spanned(token, message).to_compile_error().into();
+    }
+
+    let mut item = parse_macro_input!(item as Item);
+    match guard_item(&mut item) {
+        Ok(()) => quote!(#item).into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn is_skip(attr: &proc_macro2::TokenStream) -> bool {
+    syn::parse2::<Ident>(attr.clone()).is_ok_and(|ident| ident == "skip")
+}
+
+/// Guards the item the attribute was used on.
+fn guard_item(item: &mut Item) -> syn::Result<()> {
+    match *item {
+        Item::Fn(ref mut function) => guard_body(&function.sig, &mut function.block),
+        Item::Impl(ref mut item_impl) => guard_impl(item_impl),
+        Item::Mod(ref mut item_mod) if item_mod.content.is_none() => {
+            Err(Error::new_spanned(item_mod, "#[dont_panic] can't be used on modules in separate files"))
+        },
+        Item::Mod(ref mut item_mod) => guard_mod(item_mod),
+        _ => Err(Error::new_spanned(item, "#[dont_panic] can only be used on functions, impl blocks and inline modules")),
+    }
+}
+
+/// Guards the items of an inline module.
+///
+/// Functions in nested impl blocks, traits and inline modules are guarded too. Items generated by
+/// macros are not visible to the attribute, so they are left as they are.
+fn guard_mod(item_mod: &mut ItemMod) -> syn::Result<()> {
+    if take_skip(&mut item_mod.attrs)? {
+        return Ok(());
+    }
+    let items = match item_mod.content {
+        Some((_, ref mut items)) => items,
+        None => return Ok(()),
+    };
+    for item in items {
+        match *item {
+            Item::Fn(ref mut function) => guard_fn(&mut function.attrs, &function.sig, Some(&mut function.block))?,
+            Item::Impl(ref mut item_impl) => guard_impl(item_impl)?,
+            Item::Trait(ref mut item_trait) => guard_trait(item_trait)?,
+            Item::Mod(ref mut item_mod) => guard_mod(item_mod)?,
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Guards the functions of an impl block.
+fn guard_impl(item_impl: &mut ItemImpl) -> syn::Result<()> {
+    if take_skip(&mut item_impl.attrs)? {
+        return Ok(());
+    }
+    for impl_item in &mut item_impl.items {
+        if let ImplItem::Fn(ref mut function) = *impl_item {
+            guard_fn(&mut function.attrs, &function.sig, Some(&mut function.block))?;
+        }
+    }
+    Ok(())
+}
+
+/// Guards the default methods of a trait.
+fn guard_trait(item_trait: &mut ItemTrait) -> syn::Result<()> {
+    if take_skip(&mut item_trait.attrs)? {
+        return Ok(());
+    }
+    for trait_item in &mut item_trait.items {
+        if let TraitItem::Fn(ref mut function) = *trait_item {
+            guard_fn(&mut function.attrs, &function.sig, function.default.as_mut())?;
+        }
+    }
+    Ok(())
+}
+
+/// Guards a function inside an impl block or module unless it's marked `#[dont_panic(skip)]` or
+/// it's a `const fn`.
+fn guard_fn(attrs: &mut Vec<Attribute>, sig: &Signature, block: Option<&mut Block>) -> syn::Result<()> {
+    if take_skip(attrs)? || sig.constness.is_some() {
+        return Ok(());
+    }
+    let block = match block {
+        Some(block) => block,
+        None => return Ok(()),
+    };
+    guard_body(sig, block)
+}
+
+/// Removes `#[dont_panic]` attributes of the item, returning `true` if one of them was
+/// `#[dont_panic(skip)]`.
+///
+/// Plain `#[dont_panic]` is redundant inside another `#[dont_panic]`, removing it prevents guarding
+/// the function twice.
+fn take_skip(attrs: &mut Vec<Attribute>) -> syn::Result<bool> {
+    let mut skip = false;
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        let is_dont_panic = attr.path().segments.last().is_some_and(|segment| segment.ident == "dont_panic");
+        if !is_dont_panic {
+            return true;
+        }
+        match attr.meta {
+            Meta::Path(_) => (),
+            Meta::List(ref list) if is_skip(&list.tokens) => skip = true,
+            _ => result = Err(Error::new_spanned(attr, "expected #[dont_panic] or #[dont_panic(skip)]")),
+        }
+        false
+    });
+    result.map(|()| skip)
+}
+
+/// Rewrites every arithmetic operator in the expression to a checked operation calling
+/// `dont_panic!()` on failure.
+#[proc_macro]
+pub fn dp_arith(input: TokenStream) -> TokenStream {
+    let mut expr = parse_macro_input!(input as Expr);
+    let mut rewriter = ArithRewriter { error: None };
+    rewriter.visit_expr_mut(&mut expr);
+    match rewriter.error {
+        None => quote!(#expr).into(),
+        Some(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Replaces the body with one calling the original body through `dont_panic`.
+fn guard_body(sig: &Signature, block: &mut Block) -> syn::Result<()> {
+    if let Some(ref constness) = sig.constness {
+        return Err(Error::new_spanned(constness, "#[dont_panic] can't be used on const fn"));
+    }
+
+    // The return type is spelled out, so that `?` and conversions inside the body infer the same
+    // types they would without the attribute. `impl Trait` is not allowed there though.
+    let output = match sig.output {
+        ReturnType::Default => Some(quote!(())),
+        ReturnType::Type(_, ref ty) if contains_impl(quote!(#ty)) => None,
+        // Spelling out `!` requires an unstable feature.
+        ReturnType::Type(_, ref ty) if matches!(**ty, Type::Never(_)) => None,
+        ReturnType::Type(_, ref ty) => Some(quote!(#ty)),
+    };
+    let never = matches!(sig.output, ReturnType::Type(_, ref ty) if matches!(**ty, Type::Never(_)));
+
+    let guarded = if sig.asyncness.is_some() {
+        let guard = Ident::new("__dont_panic_guard", Span::mixed_site());
+        let result = Ident::new("__dont_panic_result", Span::mixed_site());
+        AwaitRewriter { guard: &guard }.visit_block_mut(block);
+        let output = output.map(|output| quote!(: #output));
+        quote!({
+            #[allow(unused_mut)]
+            let mut #guard = ::dont_panic::__private::Guard;
+            let #result #output = #block;
+            ::core::mem::forget(#guard);
+            #result
+        })
+    } else if never {
+        // The function never returns, so there's no exit to forget the guard at.
+        let guard = Ident::new("__dont_panic_guard", Span::mixed_site());
+        quote!({
+            let #guard = ::dont_panic::__private::Guard;
+            #block
+        })
+    } else {
+        let output = output.unwrap_or_else(|| quote!(_));
+        quote!({ ::dont_panic::call::<#output, _>(move || #block) })
+    };
+    *block = syn::parse2(guarded)?;
+    Ok(())
+}
+
+fn contains_impl(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ref ident) => ident == "impl",
+        TokenTree::Group(ref group) => contains_impl(group.stream()),
+        _ => false,
+    })
+}
+
+/// Forgets the guard of an async body before every suspension and early return, recreating it
+/// afterwards.
+///
+/// The guard must not be alive while the future is suspended, otherwise dropping the future would
+/// call `dont_panic!()`.
+struct AwaitRewriter<'a> {
+    guard: &'a Ident,
+}
+
+impl<'a> VisitMut for AwaitRewriter<'a> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        let guard = self.guard;
+        let value = Ident::new("__dont_panic_value", Span::mixed_site());
+        let rewritten = match *expr {
+            // These have their own control flow.
+            Expr::Async(_) | Expr::Closure(_) | Expr::Const(_) => return,
+            Expr::Await(ref mut await_expr) => {
+                self.visit_expr_mut(&mut await_expr.base);
+                let base = &await_expr.base;
+                // `match` keeps the temporaries of `base` alive, just like `.await` does.
+                quote!(match #base {
+                    #value => {
+                        ::core::mem::forget(#guard);
+                        let #value = #value.await;
+                        #guard = ::dont_panic::__private::Guard;
+                        #value
+                    }
+                })
+            },
+            Expr::Try(ref mut try_expr) => {
+                self.visit_expr_mut(&mut try_expr.expr);
+                let inner = &try_expr.expr;
+                quote!(match #inner {
+                    #value => {
+                        ::core::mem::forget(#guard);
+                        let #value = #value?;
+                        #guard = ::dont_panic::__private::Guard;
+                        #value
+                    }
+                })
+            },
+            Expr::Return(ref mut return_expr) => match return_expr.expr {
+                Some(ref mut inner) => {
+                    self.visit_expr_mut(inner);
+                    quote!(match #inner {
+                        #value => {
+                            ::core::mem::forget(#guard);
+                            return #value;
+                        }
+                    })
+                },
+                None => quote!({
+                    ::core::mem::forget(#guard);
+                    return;
+                }),
+            },
+            _ => return visit_mut::visit_expr_mut(self, expr),
+        };
+        *expr = Expr::Verbatim(rewritten);
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut Item) {
+        // Nested items have their own bodies.
+    }
+}
+
+/// Rewrites arithmetic operators to calls into `dont_panic::__private::Arith`.
+struct ArithRewriter {
+    error: Option<Error>,
+}
+
+impl VisitMut for ArithRewriter {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        let rewritten = match *expr {
+            Expr::Binary(ref mut binary) => {
+                let method = match binary.op {
+                    BinOp::Add(_) => "checked_add",
+                    BinOp::Sub(_) => "checked_sub",
+                    BinOp::Mul(_) => "checked_mul",
+                    BinOp::Div(_) => "checked_div",
+                    BinOp::Rem(_) => "checked_rem",
+                    BinOp::Shl(_) => "checked_shl",
+                    BinOp::Shr(_) => "checked_shr",
+                    BinOp::AddAssign(_) | BinOp::SubAssign(_) | BinOp::MulAssign(_) | BinOp::DivAssign(_) |
+                    BinOp::RemAssign(_) | BinOp::ShlAssign(_) | BinOp::ShrAssign(_) => {
+                        let error = Error::new_spanned(binary.op, "dp_arith!() doesn't support compound assignment");
+                        match self.error {
+                            Some(ref mut previous) => previous.combine(error),
+                            None => self.error = Some(error),
+                        }
+                        return;
+                    },
+                    _ => return visit_mut::visit_expr_mut(self, expr),
+                };
+                // The operation as written, for the panic message.
+                let text = quote!(#binary).to_string();
+                self.visit_expr_mut(&mut binary.left);
+                self.visit_expr_mut(&mut binary.right);
+                let method = Ident::new(method, Span::call_site());
+                let (left, right) = (unparen(&binary.left), unparen(&binary.right));
+                let divides = matches!(binary.op, BinOp::Div(_) | BinOp::Rem(_));
+                checked(quote!(::dont_panic::__private::Arith::#method(#left, #right)), binary.op.span(), &text, divides)
+            },
+            // Negative literals are left alone, their absolute value may not fit in the type
+            // (e.g. `-128i8`).
+            Expr::Unary(ref mut unary) if matches!(unary.op, UnOp::Neg(_)) && !matches!(*unary.expr, Expr::Lit(_)) => {
+                let text = quote!(#unary).to_string();
+                self.visit_expr_mut(&mut unary.expr);
+                let operand = unparen(&unary.expr);
+                checked(quote!(::dont_panic::__private::ArithNeg::checked_neg(#operand)), unary.op.span(), &text, false)
+            },
+            _ => return visit_mut::visit_expr_mut(self, expr),
+        };
+        *expr = Expr::Verbatim(rewritten);
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut Item) {
+        // Nested items are not part of the expression.
+    }
+}
+
+/// Removes the parentheses, which are not needed in function arguments.
+fn unparen(expr: &Expr) -> &Expr {
+    match *expr {
+        Expr::Paren(ref paren) => unparen(&paren.expr),
+        _ => expr,
+    }
+}
+
+/// Unwraps the result of the checked operation, calling `dont_panic!()` located at the operator if
+/// it failed.
+///
+/// Division and remainder can fail because of division by zero too, which gets its own call, so
+/// the linking error tells the two apart.
+fn checked(operation: proc_macro2::TokenStream, span: Span, text: &str, divides: bool) -> proc_macro2::TokenStream {
+    let value = Ident::new("__dont_panic_value", Span::mixed_site());
+    let error = Ident::new("__dont_panic_error", Span::mixed_site());
+    let overflow = quote_spanned!(span=> ::dont_panic::__dont_panic_category!(overflow; "{} in `{}`", #error.message(), #text));
+    let failed = if divides {
+        let div_by_zero = quote_spanned!(span=> ::dont_panic::__dont_panic_category!(div_by_zero; "{} in `{}`", #error.message(), #text));
+        quote!(match #error {
+            ::dont_panic::__private::ArithError::DivByZero(_) => #div_by_zero,
+            ::dont_panic::__private::ArithError::Overflow(_) => #overflow,
+        })
+    } else {
+        overflow
+    };
+    quote!(match #operation {
+        ::core::result::Result::Ok(#value) => #value,
+        ::core::result::Result::Err(#error) => #failed,
+    })
+}
@@ -0,0 +1,40 @@
+extern crate dont_panic;
+extern crate dont_panic_attr;
+
+use dont_panic_attr::dp_arith;
+
+fn area(width: u32, height: u32) -> u32 {
+    if width > 1024 || height > 1024 {
+        return 0;
+    }
+    dp_arith!(width * height + 2 * (width + height))
+}
+
+fn average(arr: &[u8; 4]) -> u8 {
+    let sum = dp_arith!(u16::from(arr[0]) + u16::from(arr[1]) + u16::from(arr[2]) + u16::from(arr[3]));
+    (sum / 4) as u8
+}
+
+fn abs_diff(a: i16, b: i16) -> i32 {
+    let (a, b) = (i32::from(a), i32::from(b));
+    if a > b {
+        dp_arith!(a - b)
+    } else {
+        dp_arith!(-(a - b))
+    }
+}
+
+fn bit(index: u8) -> u64 {
+    dp_arith!(1u64 << (index % 64))
+}
+
+#[test]
+fn arith() {
+    assert_eq!(area(4, 8), 56);
+    assert_eq!(area(2048, 8), 0);
+    assert_eq!(average(&[1, 2, 3, 254]), 65);
+    assert_eq!(abs_diff(-3, 4), 7);
+    assert_eq!(abs_diff(i16::MAX, i16::MIN), 65535);
+    assert_eq!(bit(65), 2);
+    assert_eq!(dp_arith!(-128i8 + 1), -127);
+}
@@ -0,0 +1,42 @@
+//! Checks the messages `dp_arith!()` panics with in `panic` mode.
+
+// Run using `cargo test --features panic`, without selecting another mode of `dont_panic`.
+#![cfg(feature = "panic")]
+
+extern crate dont_panic;
+extern crate dont_panic_attr;
+
+use dont_panic_attr::dp_arith;
+use std::hint::black_box;
+use std::panic::{self, UnwindSafe};
+
+/// Returns the message the closure panicked with.
+fn panic_message<F: FnOnce() + UnwindSafe>(f: F) -> String {
+    let payload = panic::catch_unwind(f).expect_err("didn't panic");
+    *payload.downcast::<String>().expect("the message isn't formatted")
+}
+
+#[test]
+fn overflow() {
+    let (b, max, min) = black_box((0u32, u8::MAX, i32::MIN));
+    assert_eq!(panic_message(|| { dp_arith!(max + 1); }), "attempt to add with overflow in `max + 1`");
+    assert_eq!(panic_message(|| { dp_arith!(b - 1); }), "attempt to subtract with overflow in `b - 1`");
+    assert_eq!(panic_message(|| { dp_arith!(-min); }), "attempt to negate with overflow in `- min`");
+    assert_eq!(panic_message(|| { dp_arith!(min / -1); }), "attempt to divide with overflow in `min / - 1`");
+    assert_eq!(panic_message(|| { dp_arith!(min % -1); }), "attempt to calculate the remainder with overflow in `min % - 1`");
+}
+
+#[test]
+fn div_by_zero() {
+    let (a, b) = black_box((42u32, 0u32));
+    assert_eq!(panic_message(|| { dp_arith!(a / b); }), "attempt to divide by zero in `a / b`");
+    assert_eq!(panic_message(|| { dp_arith!(a % b); }), "attempt to calculate the remainder with a divisor of zero in `a % b`");
+}
+
+#[test]
+fn assertion() {
+    let (a, b) = black_box((42u32, 0u32));
+    dont_panic::dp_assert!(dp_arith!(a + 1) > a);
+    assert_eq!(panic_message(|| dont_panic::dp_assert!(dp_arith!(b - 1) < a)), "attempt to subtract with overflow in `b - 1`");
+    assert_eq!(panic_message(|| dont_panic::dp_assert!(dp_arith!(a + 1) < a)), "assertion failed: dp_arith!(a + 1) < a");
+}
@@ -0,0 +1,230 @@
+extern crate dont_panic;
+extern crate dont_panic_attr;
+
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::task::{Context, Poll, Waker};
+
+use dont_panic_attr::dont_panic;
+
+#[dont_panic]
+fn sum(arr: &[u32; 3]) -> u32 {
+    arr[0] + arr[1] + arr[2]
+}
+
+#[dont_panic]
+fn unit(arr: &mut [u32; 3]) {
+    arr[0] = arr[1] + arr[2];
+}
+
+#[dont_panic]
+fn first<'a, T>(arr: &'a [T; 3]) -> &'a T {
+    &arr[0]
+}
+
+#[dont_panic]
+fn early_return(x: Option<u32>) -> Result<u32, ()> {
+    let x = x.ok_or(())?;
+    if x > 10 {
+        return Ok(10);
+    }
+    Ok(x)
+}
+
+#[dont_panic]
+fn display(x: &u32) -> impl Display + '_ {
+    x
+}
+
+#[dont_panic]
+fn never(x: &mut u32) -> ! {
+    loop {
+        *x = x.wrapping_add(1);
+    }
+}
+
+#[dont_panic]
+async fn async_sum(arr: [u32; 3]) -> u32 {
+    arr[0].wrapping_add(arr[1]).wrapping_add(arr[2])
+}
+
+/// Returns `Pending` once.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[dont_panic]
+async fn async_control_flow(arr: &[u32; 3], x: Option<u32>) -> Option<u32> {
+    let x = x?;
+    YieldNow(false).await;
+    if x > 10 {
+        return Some(arr[0]);
+    }
+    Some(async_sum(*arr).await.wrapping_add(x))
+}
+
+struct Counter(u32);
+
+trait Increment {
+    fn increment(&mut self) -> u32;
+}
+
+impl Counter {
+    #[dont_panic]
+    fn get(&self) -> u32 {
+        self.0
+    }
+
+    #[dont_panic]
+    fn into_inner(self) -> u32 {
+        self.0
+    }
+}
+
+impl Increment for Counter {
+    #[dont_panic]
+    fn increment(&mut self) -> u32 {
+        self.0 = self.0.wrapping_add(1);
+        self.0
+    }
+}
+
+struct Buffer([u32; 4]);
+
+#[dont_panic]
+impl Buffer {
+    fn first(&self) -> u32 {
+        self.0[0]
+    }
+
+    fn set_last(&mut self, value: u32) {
+        self.0[3] = value;
+    }
+
+    #[dont_panic(skip)]
+    fn get(&self, index: usize) -> u32 {
+        self.0[index]
+    }
+
+    // Skipped without `#[dont_panic(skip)]`, since const fns can't be guarded.
+    const fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[dont_panic]
+mod parser {
+    pub trait Parse: Sized {
+        fn parse(bytes: &[u8; 4]) -> Self;
+
+        fn parse_twice(bytes: &[u8; 4]) -> (Self, Self) {
+            (Self::parse(bytes), Self::parse(bytes))
+        }
+    }
+
+    impl Parse for u16 {
+        fn parse(bytes: &[u8; 4]) -> Self {
+            u16::from(bytes[0]) << 8 | u16::from(bytes[1])
+        }
+    }
+
+    pub fn sum(bytes: &[u8; 4]) -> u32 {
+        u32::from(bytes[0]) + u32::from(bytes[1]) + u32::from(bytes[2]) + u32::from(bytes[3])
+    }
+
+    #[dont_panic(skip)]
+    pub fn nth(bytes: &[u8], n: usize) -> u8 {
+        bytes[n]
+    }
+
+    pub mod nested {
+        pub fn last(bytes: &[u8; 4]) -> u8 {
+            bytes[3]
+        }
+    }
+}
+
+#[test]
+fn functions() {
+    let mut arr = [1, 2, 3];
+    assert_eq!(sum(&arr), 6);
+    unit(&mut arr);
+    assert_eq!(arr[0], 5);
+    assert_eq!(*first(&arr), 5);
+    assert_eq!(early_return(Some(42)), Ok(10));
+    assert_eq!(early_return(None), Err(()));
+    assert_eq!(display(&42).to_string(), "42");
+    // It never returns, so it's only checked to compile and link.
+    let _: fn(&mut u32) -> ! = never;
+}
+
+#[test]
+fn methods() {
+    let mut counter = Counter(41);
+    assert_eq!(counter.increment(), 42);
+    assert_eq!(counter.get(), 42);
+    assert_eq!(counter.into_inner(), 42);
+}
+
+#[test]
+fn impl_block() {
+    let mut buffer = Buffer([1, 2, 3, 4]);
+    buffer.set_last(42);
+    assert_eq!(buffer.first(), 1);
+    assert_eq!(buffer.get(std::hint::black_box(3)), 42);
+    assert_eq!(buffer.len(), 4);
+}
+
+#[test]
+fn module() {
+    use parser::Parse;
+
+    let bytes = [1, 2, 3, 4];
+    assert_eq!(u16::parse(&bytes), 0x0102);
+    assert_eq!(u16::parse_twice(&bytes), (0x0102, 0x0102));
+    assert_eq!(parser::sum(&bytes), 10);
+    assert_eq!(parser::nth(&bytes, std::hint::black_box(2)), 3);
+    assert_eq!(parser::nested::last(&bytes), 4);
+}
+
+#[test]
+fn async_fn() {
+    let future = pin!(async_sum([1, 2, 3]));
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(future.poll(&mut cx), Poll::Ready(6));
+}
+
+#[test]
+fn async_await() {
+    let arr = [1, 2, 3];
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let mut future = pin!(async_control_flow(&arr, Some(4)));
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(future.poll(&mut cx), Poll::Ready(Some(10)));
+
+    let mut future = pin!(async_control_flow(&arr, Some(42)));
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(future.poll(&mut cx), Poll::Ready(Some(1)));
+
+    let future = pin!(async_control_flow(&arr, None));
+    assert_eq!(future.poll(&mut cx), Poll::Ready(None));
+
+    // Dropping suspended future is fine.
+    let mut future = Box::pin(async_control_flow(&arr, Some(4)));
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    drop(future);
+}
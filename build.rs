@@ -0,0 +1,73 @@
+//! Selects the mode of `dont_panic!()`.
+//!
+//! The mode is taken from (in this order):
+//!
+//! 1. `--cfg dont_panic_mode="..."` passed to the compiler (e.g. in `RUSTFLAGS`)
+//! 2. `DONT_PANIC_MODE` environment variable
+//! 3. `panic` feature - `panic` mode
+//! 4. `auto` feature - `auto` mode
+//! 5. `link` mode otherwise
+//!
+//! The `unchecked` mode can't be selected this way. It's selected by passing
+//! `--cfg dont_panic_unsafe_assume_unreachable` to the compiler and no other mode may be selected
+//! explicitly at the same time.
+//!
+//! `auto` mode is resolved to `panic` if debug assertions of this crate are on and to `link`
+//! otherwise. The resolved mode is passed to the compiler as `dont_panic_impl` cfg, which is what
+//! the crate actually uses.
+
+use std::env;
+
+const MODES: &[&str] = &["link", "panic", "abort", "trap", "auto"];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=DONT_PANIC_MODE");
+    let values = MODES.iter().map(|mode| format!("\"{}\"", mode)).collect::<Vec<_>>().join(", ");
+    println!("cargo:rustc-check-cfg=cfg(dont_panic_mode, values({}))", values);
+    println!("cargo:rustc-check-cfg=cfg(dont_panic_impl, values({}, \"unchecked\"))", values);
+    println!("cargo:rustc-check-cfg=cfg(dont_panic_unoptimized)");
+    println!("cargo:rustc-check-cfg=cfg(dont_panic_unsafe_assume_unreachable)");
+
+    let from_cfg = env::var("CARGO_CFG_DONT_PANIC_MODE").ok();
+    let from_env = env::var("DONT_PANIC_MODE").ok().filter(|mode| !mode.is_empty());
+
+    if env::var_os("CARGO_CFG_DONT_PANIC_UNSAFE_ASSUME_UNREACHABLE").is_some() {
+        if let Some(mode) = from_cfg.or(from_env) {
+            panic!("dont_panic mode `{}` conflicts with `--cfg dont_panic_unsafe_assume_unreachable`", mode);
+        }
+        println!("cargo:rustc-cfg=dont_panic_impl=\"unchecked\"");
+        return;
+    }
+
+    let mode = match (from_cfg, from_env) {
+        (Some(ref cfg), Some(ref var)) if cfg != var => {
+            panic!("conflicting dont_panic modes: `--cfg dont_panic_mode=\"{}\"` and DONT_PANIC_MODE={}", cfg, var);
+        },
+        (Some(mode), _) | (None, Some(mode)) => mode,
+        (None, None) if env::var_os("CARGO_FEATURE_PANIC").is_some() => "panic".to_owned(),
+        (None, None) if env::var_os("CARGO_FEATURE_AUTO").is_some() => "auto".to_owned(),
+        (None, None) => "link".to_owned(),
+    };
+
+    if mode == "unchecked" {
+        panic!("`unchecked` dont_panic mode can only be selected using `--cfg dont_panic_unsafe_assume_unreachable`");
+    }
+    if !MODES.contains(&&*mode) {
+        panic!("unknown dont_panic mode `{}`, expected one of: {}", mode, MODES.join(", "));
+    }
+
+    let mode = match &*mode {
+        "auto" if env::var_os("CARGO_CFG_DEBUG_ASSERTIONS").is_some() => "panic",
+        "auto" => "link",
+        mode => mode,
+    };
+    println!("cargo:rustc-cfg=dont_panic_impl=\"{}\"", mode);
+
+    // Calls are almost never optimized-out without optimizations. Debug builds failing to link are
+    // expected, but release builds failing is confusing. The macros turn this into a compile error
+    // in the crates calling them.
+    if mode == "link" && env::var("PROFILE").is_ok_and(|profile| profile == "release") && env::var("OPT_LEVEL").is_ok_and(|level| level == "0") {
+        println!("cargo:rustc-cfg=dont_panic_unoptimized");
+    }
+}
@@ -0,0 +1,236 @@
+//! Explains link errors caused by `dont_panic!()` calls which weren't optimized-out.
+//!
+//! The tool scans object files and rlibs for references to the symbols `dont_panic!()` calls and
+//! prints a diagnostic for each of them, using debug info to find the enclosing (possibly inlined)
+//! functions.
+//!
+//! # Usage
+//!
+//! Object files of the final crate are deleted after linking, so build it with `-C save-temps` to
+//! keep them around. Enabling debug info makes the output much more helpful:
+//!
+//! ```text
+//! RUSTFLAGS="-C save-temps -C debuginfo=1" cargo build --release
+//! dont-panic-explain target/release/deps
+//! ```
+
+extern crate addr2line;
+extern crate gimli;
+extern crate object;
+extern crate rustc_demangle;
+
+mod scan;
+mod symbol;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use std::collections::BTreeMap;
+
+use scan::{Frame, PanicCall, Reference, Report, Scanner, Site};
+
+const USAGE: &str = "Usage: dont-panic-explain [--all] [--list] [--crate NAME] PATH...
+
+Finds calls to dont_panic!() which weren't optimized-out in object files, rlibs and static
+libraries. Directories are searched recursively, skipping object files left behind by earlier
+builds.
+
+Options:
+    --all           Report calls in code unreachable from `main` too
+    --list          List all dont_panic!() calls found in the site records
+    --crate NAME    Only scan object files of crate NAME found in directories, NAME may be
+                    followed by `-` and the hash in the file names";
+
+fn main() {
+    let mut all = false;
+    let mut list = false;
+    let mut krate = None;
+    let mut paths = Vec::new();
+    let mut args = env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            println!("{}", USAGE);
+            return;
+        } else if arg == "--all" {
+            all = true;
+        } else if arg == "--list" {
+            list = true;
+        } else if arg == "--crate" {
+            match args.next().and_then(|name| name.into_string().ok()) {
+                Some(name) => krate = Some(name),
+                None => {
+                    eprintln!("{}", USAGE);
+                    process::exit(2);
+                },
+            }
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    if paths.is_empty() {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    }
+
+    let mut scanner = Scanner::new();
+    if let Some(ref krate) = krate {
+        scanner.select_crate(krate);
+    }
+    for path in &paths {
+        if let Err(error) = scanner.scan_path(Path::new(path)) {
+            eprintln!("error: failed to scan {}: {}", Path::new(path).display(), error);
+            process::exit(2);
+        }
+    }
+
+    let Report { mut references, mut panics, sites } = scanner.finish(all);
+    references.sort();
+    references.dedup();
+    panics.sort();
+    panics.dedup();
+
+    if list {
+        print_sites(&sites, &references);
+    }
+
+    for reference in &references {
+        let candidates = reference.symbol.location.as_ref()
+            .and_then(|location| sites.get(&location.site_id()))
+            .map_or_else(Vec::new, |sites| sites.iter().filter(|site| site.matches(&reference.symbol)).collect());
+        print_reference(reference, &candidates);
+    }
+
+    // Calls to panicking functions are fine, unless they make our panic handler reachable.
+    if references.iter().any(is_in_panic_handler) {
+        for panic in &panics {
+            print_panic(panic);
+        }
+    }
+
+    match references.len() {
+        0 => println!("no dont_panic!() calls which weren't optimized-out were found"),
+        1 => {
+            println!("error: found 1 dont_panic!() call which wasn't optimized-out");
+            process::exit(1);
+        },
+        count => {
+            println!("error: found {} dont_panic!() calls which weren't optimized-out", count);
+            process::exit(1);
+        },
+    }
+}
+
+fn print_sites(sites: &BTreeMap<String, Vec<Site>>, references: &[Reference]) {
+    for (id, sites) in sites {
+        for site in sites {
+            let survived = references.iter().any(|reference| {
+                reference.symbol.location.as_ref().is_some_and(|location| location.site_id() == *id) && site.matches(&reference.symbol)
+            });
+            let status = if survived { "not optimized-out" } else { "ok" };
+            println!("{}: {}!({}) [{}]", id, site.macro_name, site.arguments, status);
+        }
+    }
+    println!();
+}
+
+/// Checks whether the `dont_panic!()` call is in the `#[panic_handler]`.
+fn is_in_panic_handler(reference: &Reference) -> bool {
+    reference.function.as_ref().is_some_and(|function| scan::is_panic_handler(function))
+}
+
+fn print_reference(reference: &Reference, sites: &[&Site]) {
+    let location = reference.symbol.location.as_ref()
+        .map(|location| (location.file.clone(), Some(location.line), Some(location.column)));
+
+    println!("error: {}!() call wasn't optimized-out ({})", reference.symbol.macro_name, reference.symbol.category);
+    let gutter = print_frames(location, &reference.frames, reference.function.as_ref());
+    match sites {
+        [] => (),
+        [site] => println!("{:gutter$} = note: called as `{}!({})`", "", site.macro_name, site.arguments, gutter = gutter),
+        sites => {
+            println!("{:gutter$} = note: called as one of:", "", gutter = gutter);
+            for site in sites {
+                println!("{:gutter$}         `{}!({})`", "", site.macro_name, site.arguments, gutter = gutter);
+            }
+        },
+    }
+    println!("{:gutter$} = note: undefined symbol `{}` referenced from {}", "", reference.symbol.name, reference.object, gutter = gutter);
+    println!();
+}
+
+fn print_panic(panic: &PanicCall) {
+    println!("error: call to `{}` makes the panic handler reachable", panic.callee);
+    let gutter = print_frames(None, &panic.frames, panic.function.as_ref());
+    println!("{:gutter$} = note: referenced from {}", "", panic.object, gutter = gutter);
+    println!();
+}
+
+/// Prints the location, the snippet and the functions the code was inlined into, returns the width
+/// of the gutter.
+///
+/// The location of the innermost frame is used if `location` is `None`.
+fn print_frames(location: Option<(String, Option<u32>, Option<u32>)>, frames: &[Frame], function: Option<&String>) -> usize {
+    let innermost = frames.first();
+    let location = location.or_else(|| innermost.and_then(|frame| frame.file.clone().map(|file| (file, frame.line, frame.column))));
+
+    let line = location.as_ref().and_then(|location| location.1);
+    let gutter = line.map_or(0, |line| line.to_string().len());
+    match location {
+        Some((ref file, line, column)) => {
+            println!("{:gutter$}--> {}", "", format_location(file, line, column), gutter = gutter);
+            // Debug info contains absolute paths, prefer them if they refer to the same file.
+            let source = innermost
+                .and_then(|frame| frame.file.as_ref())
+                .filter(|source| Path::new(source).ends_with(file))
+                .unwrap_or(file);
+            if let Some(line) = line {
+                print_snippet(source, line, column, gutter);
+            }
+        },
+        None => println!("{:gutter$}--> <unknown location>", "", gutter = gutter),
+    }
+
+    println!("{:gutter$} |", "", gutter = gutter);
+    let function = innermost.and_then(|frame| frame.function.as_ref()).or(function);
+    if let Some(function) = function {
+        println!("{:gutter$} = note: in function `{}`", "", function, gutter = gutter);
+    }
+    for frame in frames.iter().skip(1) {
+        let function = frame.function.as_ref().map_or("<unknown>", |function| &**function);
+        match frame.file {
+            Some(ref file) => println!("{:gutter$} = note: inlined into `{}` at {}", "", function, format_location(file, frame.line, frame.column), gutter = gutter),
+            None => println!("{:gutter$} = note: inlined into `{}`", "", function, gutter = gutter),
+        }
+    }
+    gutter
+}
+
+fn format_location(file: &str, line: Option<u32>, column: Option<u32>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) if column > 0 => format!("{}:{}:{}", file, line, column),
+        (Some(line), _) => format!("{}:{}", file, line),
+        (None, _) => file.to_owned(),
+    }
+}
+
+fn print_snippet(file: &str, line: u32, column: Option<u32>, gutter: usize) {
+    let source = match fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(_) => return,
+    };
+
+    let text = match source.lines().nth((line as usize).saturating_sub(1)) {
+        Some(text) => text,
+        None => return,
+    };
+
+    println!("{:gutter$} |", "", gutter = gutter);
+    println!("{} | {}", line, text);
+    if let Some(column) = column.filter(|column| *column > 0) {
+        let indent = text.chars().take(column as usize - 1).map(|c| if c == '\t' { '\t' } else { ' ' }).collect::<String>();
+        println!("{:gutter$} | {}^", "", indent, gutter = gutter);
+    }
+}
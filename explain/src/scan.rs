@@ -0,0 +1,749 @@
+//! Finding references to `dont_panic!()` symbols in object files and archives.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use addr2line;
+use gimli;
+use object::{self, Object, ObjectSection, ObjectSymbol, RelocationKind, RelocationTarget, SectionIndex, SectionKind, SymbolKind};
+use object::read::archive::ArchiveFile;
+use rustc_demangle;
+
+use symbol::Symbol;
+
+/// A place in the machine code which references a `dont_panic!()` symbol.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Reference {
+    pub symbol: Symbol,
+    /// The object file containing the reference.
+    pub object: String,
+    /// The function containing the reference according to the symbol table.
+    pub function: Option<String>,
+    /// Inlined frames according to debug info, innermost first. Empty if there's no debug info.
+    pub frames: Vec<Frame>,
+}
+
+/// A call to a `core` function which panics.
+///
+/// These are interesting when the panic handler calls `dont_panic!()`, since they are what makes
+/// the handler reachable.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PanicCall {
+    /// The demangled name of the called function.
+    pub callee: String,
+    /// The object file containing the call.
+    pub object: String,
+    /// The function containing the call according to the symbol table.
+    pub function: Option<String>,
+    /// Inlined frames according to debug info, innermost first. Empty if there's no debug info.
+    pub frames: Vec<Frame>,
+}
+
+/// A `dont_panic!()` call described by a record in the `.dont_panic_sites` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Site {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    /// The arguments of the macro as written in the source code.
+    pub arguments: String,
+    /// The name of the macro, e.g. `dont_panic`.
+    pub macro_name: String,
+    /// The prefix of the symbol the call references, e.g. `dont_panic_overflow`.
+    ///
+    /// Records produced by older versions of `dont_panic` don't contain it.
+    pub prefix: Option<String>,
+}
+
+impl Site {
+    /// Checks whether the symbol could have been referenced by this call.
+    ///
+    /// The symbol must have the same site id already; several calls may share it.
+    pub fn matches(&self, symbol: &Symbol) -> bool {
+        self.prefix.as_ref().is_none_or(|prefix| symbol.name.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.starts_with('$')))
+    }
+}
+
+/// The result of scanning.
+pub struct Report {
+    pub references: Vec<Reference>,
+    pub panics: Vec<PanicCall>,
+    /// All sites we found records for, keyed by site id.
+    ///
+    /// Calls expanded from a single macro invocation share the id, so there may be several of them.
+    pub sites: BTreeMap<String, Vec<Site>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Frame {
+    pub function: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+/// Collects references from object files along with enough information to tell which of them the
+/// linker would keep.
+#[derive(Default)]
+pub struct Scanner {
+    references: Vec<(usize, Reference)>,
+    panics: Vec<(usize, PanicCall)>,
+    sites: BTreeMap<String, Vec<Site>>,
+    /// Outgoing edges of every section we've seen, indexed by global section id.
+    sections: Vec<Vec<Edge>>,
+    /// Sections defining global symbols.
+    globals: HashMap<String, usize>,
+    /// The section defining `main` along with the name of the object containing it.
+    main: Option<(usize, String)>,
+    /// The section defining the panic handler.
+    panic_handler: Option<usize>,
+    /// The crate whose object files are scanned when scanning directories, if restricted.
+    krate: Option<String>,
+}
+
+enum Edge {
+    Section(usize),
+    Symbol(String),
+}
+
+/// Undefined symbol we're interested in.
+enum Target {
+    DontPanic(Symbol),
+    Panic(String),
+}
+
+/// Names of the entry point, the root of reachability.
+const ROOTS: &[&str] = &["main", "_main"];
+
+/// Functions in `core` which panic, matched as prefixes of demangled names.
+const PANICKING_FUNCTIONS: &[&str] = &[
+    "core::panicking::",
+    "core::option::unwrap_failed",
+    "core::option::expect_failed",
+    "core::result::unwrap_failed",
+    "core::slice::index::slice_",
+    "core::str::slice_error_fail",
+    "core::cell::panic_already_",
+];
+
+impl Scanner {
+    pub fn new() -> Self {
+        Scanner::default()
+    }
+
+    /// Restricts the object files scanned in directories to the ones of the given crate.
+    ///
+    /// The name is either the crate name or the crate name followed by `-` and the hash cargo
+    /// appends to the file names. Rlibs and static libraries are scanned regardless.
+    pub fn select_crate(&mut self, name: &str) {
+        self.krate = Some(name.to_owned());
+    }
+
+    /// Scans a file or recursively a directory.
+    ///
+    /// Only object files (`*.o`), rlibs and static libraries are considered when scanning
+    /// directories. Object files left behind by earlier builds are skipped, see `latest_objects`.
+    pub fn scan_path(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if !path.is_dir() {
+            let data = fs::read(path)?;
+            return self.scan_data(&path.display().to_string(), &data);
+        }
+
+        let mut objects = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            match path.extension() {
+                _ if path.is_dir() => self.scan_path(&path)?,
+                Some(ext) if ext == "rlib" || ext == "a" => self.scan_path(&path)?,
+                Some(ext) if ext == "o" => {
+                    let object = ObjectFile::new(path)?;
+                    if self.krate.as_ref().is_none_or(|krate| object.is_of_crate(krate)) {
+                        objects.push(object);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        for path in latest_objects(objects) {
+            self.scan_path(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the references the linker would complain about along with all site records.
+    ///
+    /// Objects usually contain code that doesn't end up in the final binary, so, like the linker,
+    /// we only consider sections reachable from `main`. If `all` is `true` or there is no `main`
+    /// (e.g. when scanning libraries only), all references are returned.
+    pub fn finish(self, all: bool) -> Report {
+        let root = match self.main {
+            Some((section, _)) if !all => section,
+            _ => return Report {
+                references: self.references.into_iter().map(|(_, reference)| reference).collect(),
+                panics: self.panics.into_iter().map(|(_, panic)| panic).collect(),
+                sites: self.sites,
+            },
+        };
+
+        let mut reachable = vec![false; self.sections.len()];
+        let mut stack = vec![root];
+        while let Some(section) = stack.pop() {
+            if reachable[section] {
+                continue;
+            }
+            reachable[section] = true;
+
+            for edge in &self.sections[section] {
+                let target = match *edge {
+                    Edge::Section(target) => Some(target),
+                    // Panicking functions are defined in `core`, which we usually don't scan, but
+                    // we know they call the panic handler.
+                    Edge::Symbol(ref name) => self.globals.get(name).cloned()
+                        .or_else(|| panicking_function(name).and(self.panic_handler)),
+                };
+                stack.extend(target);
+            }
+        }
+
+        Report {
+            references: self.references.into_iter()
+                .filter(|&(section, _)| reachable[section])
+                .map(|(_, reference)| reference)
+                .collect(),
+            panics: self.panics.into_iter()
+                .filter(|&(section, _)| reachable[section])
+                .map(|(_, panic)| panic)
+                .collect(),
+            sites: self.sites,
+        }
+    }
+
+    fn scan_data(&mut self, name: &str, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        if let Ok(archive) = ArchiveFile::parse(data) {
+            for member in archive.members() {
+                let member = member?;
+                let member_name = format!("{}({})", name, String::from_utf8_lossy(member.name()));
+                // rlibs contain metadata too, we're only interested in object files.
+                if let Ok(file) = object::File::parse(member.data(data)?) {
+                    self.scan_object(&member_name, &file)?;
+                }
+            }
+            Ok(())
+        } else {
+            self.scan_object(name, &object::File::parse(data)?)
+        }
+    }
+
+    fn scan_object(&mut self, name: &str, file: &object::File) -> Result<(), Box<dyn Error>> {
+        let layout = Layout::new(file);
+        // Debug info is only a nice-to-have, we can still report the symbols without it.
+        let sections = load_dwarf(file, &layout).ok();
+        let endian = if file.is_little_endian() { gimli::RunTimeEndian::Little } else { gimli::RunTimeEndian::Big };
+        let context = sections.as_ref().and_then(|sections| {
+            let dwarf = sections.borrow(|section| {
+                gimli::RelocateReader::new(gimli::EndianSlice::new(&section.data, endian), &section.relocations)
+            });
+            addr2line::Context::from_dwarf(dwarf).ok()
+        });
+
+        if let Some(section) = file.section_by_name(".dont_panic_sites") {
+            self.read_sites(file, &section)?;
+        }
+
+        let base = self.sections.len();
+        let ids = file.sections().enumerate().map(|(i, section)| (section.index(), base + i)).collect::<HashMap<_, _>>();
+        self.sections.extend(file.sections().map(|_| Vec::new()));
+
+        for symbol in file.symbols() {
+            if let (true, Some(section)) = (symbol.is_global(), symbol.section_index()) {
+                if let (Ok(symbol), Some(&id)) = (symbol.name(), ids.get(&section)) {
+                    self.globals.entry(symbol.to_owned()).or_insert(id);
+                    if is_panic_handler(symbol) {
+                        self.panic_handler.get_or_insert(id);
+                    }
+                    if ROOTS.contains(&symbol) {
+                        self.add_main(id, name)?;
+                    }
+                }
+            }
+        }
+
+        for section in file.sections() {
+            let id = ids[&section.index()];
+            for (offset, relocation) in section.relocations() {
+                let symbol = match relocation.target() {
+                    RelocationTarget::Symbol(index) => file.symbol_by_index(index)?,
+                    RelocationTarget::Section(index) => {
+                        self.sections[id].extend(ids.get(&index).map(|&target| Edge::Section(target)));
+                        continue;
+                    },
+                    _ => continue,
+                };
+
+                match symbol.section_index().and_then(|index| ids.get(&index)) {
+                    Some(&target) => self.sections[id].push(Edge::Section(target)),
+                    None => self.sections[id].push(Edge::Symbol(symbol.name()?.to_owned())),
+                }
+
+                if !symbol.is_undefined() || section.kind() != SectionKind::Text {
+                    continue;
+                }
+
+                let target = match Symbol::parse(symbol.name()?) {
+                    Some(symbol) => Target::DontPanic(symbol),
+                    None => match panicking_function(symbol.name()?) {
+                        Some(callee) => Target::Panic(callee),
+                        None => continue,
+                    },
+                };
+
+                let frames = match context {
+                    Some(ref context) => find_frames(context, layout.address(section.index()) + offset)?,
+                    None => Vec::new(),
+                };
+                let object = name.to_owned();
+                let function = enclosing_function(file, section.index(), offset);
+
+                match target {
+                    Target::DontPanic(symbol) => self.references.push((id, Reference { symbol, object, function, frames })),
+                    Target::Panic(callee) => self.panics.push((id, PanicCall { callee, object, function, frames })),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records the section defining `main`.
+    ///
+    /// Reachability from one `main` tells nothing about the objects of another binary, so we refuse
+    /// to guess which of them the user is interested in.
+    fn add_main(&mut self, section: usize, object: &str) -> Result<(), Box<dyn Error>> {
+        match self.main {
+            // The same object may be scanned more than once.
+            Some((_, ref main)) if main == object => Ok(()),
+            Some((_, ref main)) => Err(format!("`main` is defined in both {} and {}, scan the objects of a single binary (see `--crate`)", main, object).into()),
+            None => {
+                self.main = Some((section, object.to_owned()));
+                Ok(())
+            },
+        }
+    }
+
+    /// Reads the records pointed to by the `.dont_panic_sites` section.
+    fn read_sites(&mut self, file: &object::File, section: &object::Section) -> Result<(), Box<dyn Error>> {
+        let data = section.data()?;
+        for (offset, relocation) in section.relocations() {
+            let (index, address) = match relocation.target() {
+                RelocationTarget::Symbol(index) => {
+                    let symbol = file.symbol_by_index(index)?;
+                    match symbol.section_index() {
+                        Some(index) => (index, symbol.address()),
+                        None => continue,
+                    }
+                },
+                RelocationTarget::Section(index) => (index, 0),
+                _ => continue,
+            };
+
+            let addend = if relocation.has_implicit_addend() {
+                read_implicit_addend(data, offset as usize, relocation.size(), file.is_little_endian())
+            } else {
+                relocation.addend()
+            };
+
+            let record = file.section_by_index(index)?.data()?;
+            let record = match record.get(address.wrapping_add(addend as u64) as usize..) {
+                Some(record) => record,
+                None => continue,
+            };
+
+            let mut fields = record.splitn(8, |&byte| byte == 0).map(String::from_utf8_lossy);
+            let (id, file, line, column, arguments) = match (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some(id), Some(file), Some(line), Some(column), Some(arguments)) => (id, file, line, column, arguments),
+                _ => continue,
+            };
+            // Records produced by older versions of `dont_panic` don't contain the name.
+            let macro_name = fields.next().filter(|name| !name.is_empty()).map_or_else(|| "dont_panic".to_owned(), |name| name.into_owned());
+            let prefix = fields.next().filter(|prefix| !prefix.is_empty()).map(Cow::into_owned);
+
+            let site = Site {
+                file: file.into_owned(),
+                line: line.parse()?,
+                column: column.parse()?,
+                arguments: arguments.into_owned(),
+                macro_name,
+                prefix,
+            };
+            // The same object may be scanned more than once, e.g. in an archive and on its own.
+            let sites = self.sites.entry(id.into_owned()).or_default();
+            if !sites.contains(&site) {
+                sites.push(site);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An object file found when scanning a directory.
+#[derive(Debug)]
+struct ObjectFile {
+    path: PathBuf,
+    modified: SystemTime,
+    /// The modification time of the dep-info file of the unit, if there's one.
+    dep_info: Option<SystemTime>,
+}
+
+impl ObjectFile {
+    fn new(path: PathBuf) -> Result<Self, Box<dyn Error>> {
+        let modified = fs::metadata(&path)?.modified()?;
+        let dep_info = path.file_name()
+            .map(|name| path.with_file_name(format!("{}.d", unit_name(&name.to_string_lossy()))))
+            .and_then(|dep_info| fs::metadata(dep_info).and_then(|metadata| metadata.modified()).ok());
+        Ok(ObjectFile { path, modified, dep_info })
+    }
+
+    fn name(&self) -> Cow<'_, str> {
+        self.path.file_name().map_or(Cow::Borrowed(""), |name| name.to_string_lossy())
+    }
+
+    /// The name of the compilation unit, `<crate>-<hash>` for objects produced by cargo builds.
+    fn unit(&self) -> String {
+        unit_name(&self.name()).to_owned()
+    }
+
+    /// The token rustc puts into the names of objects to tell invocations apart.
+    ///
+    /// Only incremental builds use it, the objects are named `<unit>.<cgu>.<invocation>.rcgu.o`.
+    fn invocation(&self) -> Option<String> {
+        let name = self.name();
+        let mut parts = name.strip_suffix(".rcgu.o")?.split('.').skip(1);
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(_), Some(invocation), None) => Some(invocation.to_owned()),
+            _ => None,
+        }
+    }
+
+    fn is_of_crate(&self, krate: &str) -> bool {
+        let unit = self.unit();
+        unit == krate || unit.strip_prefix(krate).is_some_and(|hash| hash.starts_with('-'))
+    }
+}
+
+fn unit_name(file_name: &str) -> &str {
+    file_name.split('.').next().unwrap_or(file_name)
+}
+
+/// Keeps only the objects written by the latest rustc invocation of each unit.
+///
+/// rustc doesn't delete object files kept by `-C save-temps`, so the directory usually contains
+/// stale codegen units of earlier builds, referencing symbols that no longer exist.
+///
+/// Incremental builds hard link the codegen units they reuse, keeping their modification times,
+/// but they put a token unique to the invocation into the names. Other builds write all codegen
+/// units again, so anything older than the dep-info file, which rustc writes before generating
+/// code, is stale.
+fn latest_objects(objects: Vec<ObjectFile>) -> Vec<PathBuf> {
+    let mut units = BTreeMap::<String, Vec<ObjectFile>>::new();
+    for object in objects {
+        units.entry(object.unit()).or_default().push(object);
+    }
+
+    let mut paths = Vec::new();
+    for (_, objects) in units {
+        let invocation = objects.iter().max_by_key(|object| object.modified).and_then(ObjectFile::invocation);
+        paths.extend(objects.into_iter()
+            .filter(|object| match invocation {
+                Some(ref invocation) => object.invocation().as_ref() == Some(invocation),
+                None => object.dep_info.is_none_or(|dep_info| object.modified >= dep_info),
+            })
+            .map(|object| object.path));
+    }
+    paths.sort();
+    paths
+}
+
+fn read_implicit_addend(data: &[u8], offset: usize, size: u8, little_endian: bool) -> i64 {
+    let bytes = match data.get(offset..offset + usize::from(size / 8)) {
+        Some(bytes) => bytes,
+        None => return 0,
+    };
+
+    let value = if little_endian {
+        bytes.iter().rev().fold(0u64, |value, &byte| value << 8 | u64::from(byte))
+    } else {
+        bytes.iter().fold(0u64, |value, &byte| value << 8 | u64::from(byte))
+    };
+    value as i64
+}
+
+/// Checks whether the symbol is the `#[panic_handler]`.
+pub fn is_panic_handler(name: &str) -> bool {
+    format!("{:#}", rustc_demangle::demangle(name)).ends_with("rust_begin_unwind")
+}
+
+/// Returns the demangled name of the function if it's one of the `core` functions which panic.
+fn panicking_function(name: &str) -> Option<String> {
+    let name = format!("{:#}", rustc_demangle::demangle(name));
+    if PANICKING_FUNCTIONS.iter().any(|prefix| name.starts_with(prefix)) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+fn enclosing_function(file: &object::File, section: SectionIndex, offset: u64) -> Option<String> {
+    file.symbols()
+        .filter(|symbol| symbol.kind() == SymbolKind::Text && symbol.section_index() == Some(section))
+        .filter(|symbol| symbol.address() <= offset && (symbol.size() == 0 || offset < symbol.address() + symbol.size()))
+        .max_by_key(|symbol| symbol.address())
+        .and_then(|symbol| symbol.name().ok().map(|name| format!("{:#}", rustc_demangle::demangle(name))))
+}
+
+fn find_frames<R: gimli::Reader>(context: &addr2line::Context<R>, address: u64) -> Result<Vec<Frame>, Box<dyn Error>> {
+    let mut frames = Vec::new();
+    let mut iter = context.find_frames(address).skip_all_loads()?;
+    while let Some(frame) = iter.next()? {
+        let function = match frame.function {
+            Some(ref function) => Some(function.demangle()?.into_owned()),
+            None => None,
+        };
+
+        frames.push(Frame {
+            function,
+            file: frame.location.as_ref().and_then(|location| location.file.map(ToOwned::to_owned)),
+            line: frame.location.as_ref().and_then(|location| location.line),
+            column: frame.location.as_ref().and_then(|location| location.column),
+        });
+    }
+    Ok(frames)
+}
+
+/// Assigns distinct addresses to sections of a relocatable object file.
+///
+/// All sections of a relocatable object start at address zero, which makes addresses in debug info
+/// ambiguous. We lay the sections out one after another, like a linker would, and relocate the debug
+/// info accordingly.
+struct Layout(HashMap<SectionIndex, u64>);
+
+impl Layout {
+    fn new(file: &object::File) -> Self {
+        let mut addresses = HashMap::new();
+        let mut next = 0u64;
+        for section in file.sections() {
+            // We only care about code. References to other sections (notably debug sections, which
+            // are referenced by offsets) have to stay at zero.
+            if section.kind() != SectionKind::Text {
+                continue;
+            }
+
+            let align = section.align().max(1);
+            let address = next.div_ceil(align) * align;
+            addresses.insert(section.index(), address);
+            next = address + section.size().max(1);
+        }
+        Layout(addresses)
+    }
+
+    fn address(&self, section: SectionIndex) -> u64 {
+        self.0.get(&section).cloned().unwrap_or(0)
+    }
+}
+
+struct DebugSection<'data> {
+    data: Cow<'data, [u8]>,
+    relocations: Relocations,
+}
+
+/// Relocations of a single debug section, keyed by offset.
+#[derive(Debug, Default)]
+struct Relocations(HashMap<usize, Relocation>);
+
+#[derive(Debug)]
+struct Relocation {
+    implicit_addend: bool,
+    value: u64,
+}
+
+impl Relocations {
+    fn new(file: &object::File, section: &object::Section, layout: &Layout) -> Result<Self, object::Error> {
+        let mut relocations = HashMap::new();
+        for (offset, relocation) in section.relocations() {
+            if relocation.kind() != RelocationKind::Absolute {
+                continue;
+            }
+
+            let target = match relocation.target() {
+                RelocationTarget::Symbol(index) => {
+                    let symbol = file.symbol_by_index(index)?;
+                    let base = symbol.section_index().map_or(0, |section| layout.address(section));
+                    base + symbol.address()
+                },
+                RelocationTarget::Section(index) => layout.address(index),
+                _ => continue,
+            };
+
+            relocations.insert(offset as usize, Relocation {
+                implicit_addend: relocation.has_implicit_addend(),
+                value: target.wrapping_add(relocation.addend() as u64),
+            });
+        }
+        Ok(Relocations(relocations))
+    }
+
+    fn relocate(&self, offset: usize, value: u64) -> u64 {
+        match self.0.get(&offset) {
+            Some(relocation) if relocation.implicit_addend => value.wrapping_add(relocation.value),
+            Some(relocation) => relocation.value,
+            None => value,
+        }
+    }
+}
+
+impl gimli::Relocate for &Relocations {
+    fn relocate_address(&self, offset: usize, value: u64) -> gimli::Result<u64> {
+        Ok(self.relocate(offset, value))
+    }
+
+    fn relocate_offset(&self, offset: usize, value: usize) -> gimli::Result<usize> {
+        <usize as gimli::ReaderOffset>::from_u64(self.relocate(offset, value as u64))
+    }
+}
+
+fn load_dwarf<'data>(file: &object::File<'data>, layout: &Layout) -> Result<gimli::DwarfSections<DebugSection<'data>>, object::Error> {
+    gimli::DwarfSections::load(|id| {
+        match file.section_by_name(id.name()) {
+            Some(section) => Ok(DebugSection {
+                data: section.uncompressed_data()?,
+                relocations: Relocations::new(file, &section, layout)?,
+            }),
+            None => Ok(DebugSection {
+                data: Cow::Borrowed(&[]),
+                relocations: Relocations::default(),
+            }),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use super::{latest_objects, ObjectFile, Scanner, Site};
+
+    /// Built from `tests/fixtures/reachability.rs`, see the instructions there.
+    const FIXTURE: &[u8] = include_bytes!("../tests/fixtures/reachability.o");
+    const SOURCE: &str = "explain/tests/fixtures/reachability.rs";
+
+    fn scan(all: bool) -> super::Report {
+        let mut scanner = Scanner::new();
+        scanner.scan_data("reachability.o", FIXTURE).unwrap();
+        scanner.finish(all)
+    }
+
+    #[test]
+    fn unreachable_references_are_ignored() {
+        let report = scan(false);
+        let names = report.references.iter().map(|reference| &*reference.symbol.name).collect::<Vec<_>>();
+        assert_eq!(names, [format!("rust_panic_called_where_shouldnt$reachability${}:19:9", SOURCE)]);
+    }
+
+    #[test]
+    fn all_references() {
+        let report = scan(true);
+        let mut names = report.references.iter().map(|reference| reference.symbol.macro_name).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["dont_panic", "dp_unreachable"]);
+    }
+
+    #[test]
+    fn inlined_frames() {
+        let report = scan(false);
+        let frames = report.references[0].frames.iter()
+            .map(|frame| (frame.function.as_deref(), frame.file.as_deref(), frame.line))
+            .collect::<Vec<_>>();
+        assert_eq!(frames, [
+            (Some("reachability::check"), Some(SOURCE), Some(19)),
+            (Some("reachability::main"), Some(SOURCE), Some(35)),
+        ]);
+    }
+
+    #[test]
+    fn site_records() {
+        let report = scan(false);
+        let id = format!("reachability${}:19:9", SOURCE);
+        assert_eq!(report.sites.len(), 2);
+        assert_eq!(report.sites[&id], [Site {
+            file: SOURCE.to_owned(),
+            line: 19,
+            column: 9,
+            arguments: "\"the answer is {}\", value".to_owned(),
+            macro_name: "dont_panic".to_owned(),
+            prefix: Some("rust_panic_called_where_shouldnt".to_owned()),
+        }]);
+    }
+
+    #[test]
+    fn duplicate_main() {
+        let mut scanner = Scanner::new();
+        scanner.scan_data("reachability.o", FIXTURE).unwrap();
+        scanner.scan_data("reachability.o", FIXTURE).unwrap();
+        let error = scanner.scan_data("other.o", FIXTURE).unwrap_err();
+        assert!(error.to_string().starts_with("`main` is defined in both reachability.o and other.o"));
+    }
+
+    fn object(name: &str, modified: u64, dep_info: Option<u64>) -> ObjectFile {
+        ObjectFile {
+            path: PathBuf::from(name),
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(modified),
+            dep_info: dep_info.map(|dep_info| SystemTime::UNIX_EPOCH + Duration::from_secs(dep_info)),
+        }
+    }
+
+    #[test]
+    fn latest_incremental_objects() {
+        let objects = vec![
+            object("foo-1234.abc.0y7ofw8.rcgu.o", 10, Some(20)),
+            object("foo-1234.def.0y7ofw8.rcgu.o", 10, Some(20)),
+            // Reused codegen unit, hard linked.
+            object("foo-1234.abc.0vookmi.rcgu.o", 10, Some(20)),
+            object("foo-1234.def.0vookmi.rcgu.o", 21, Some(20)),
+            object("bar-5678.abc.1favgku.rcgu.o", 5, None),
+        ];
+        assert_eq!(latest_objects(objects), [
+            PathBuf::from("bar-5678.abc.1favgku.rcgu.o"),
+            PathBuf::from("foo-1234.abc.0vookmi.rcgu.o"),
+            PathBuf::from("foo-1234.def.0vookmi.rcgu.o"),
+        ]);
+    }
+
+    #[test]
+    fn latest_objects_by_dep_info() {
+        let objects = vec![
+            object("foo-1234.foo.5678-cgu.0.rcgu.o", 21, Some(20)),
+            object("foo-1234.foo.5678-cgu.1.rcgu.o", 10, Some(20)),
+            object("foo-1234.abcdef.rcgu.o", 20, Some(20)),
+            object("bar-5678.bar.1234-cgu.0.rcgu.o", 5, None),
+        ];
+        assert_eq!(latest_objects(objects), [
+            PathBuf::from("bar-5678.bar.1234-cgu.0.rcgu.o"),
+            PathBuf::from("foo-1234.abcdef.rcgu.o"),
+            PathBuf::from("foo-1234.foo.5678-cgu.0.rcgu.o"),
+        ]);
+    }
+
+    #[test]
+    fn crate_selection() {
+        let object = object("foo_bar-1234.abc.rcgu.o", 0, None);
+        assert!(object.is_of_crate("foo_bar"));
+        assert!(object.is_of_crate("foo_bar-1234"));
+        assert!(!object.is_of_crate("foo"));
+        assert!(!object.is_of_crate("foo_bar-12"));
+    }
+}
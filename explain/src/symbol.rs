@@ -0,0 +1,163 @@
+//! Recognizing and decoding the symbols referenced by `dont_panic!()` calls.
+
+/// Prefixes of the symbols referenced by `dont_panic!()` and similar macros, along with the names
+/// of the macros and the categories of the problems.
+pub const PREFIXES: &[(&str, &str, &str)] = &[
+    ("rust_panic_called_where_shouldnt", "dont_panic", "user-defined"),
+    ("rust_unreachable_called_where_shouldnt", "dp_unreachable", "unreachable"),
+    ("rust_unimplemented_called_where_shouldnt", "dp_unimplemented", "unimplemented"),
+    ("rust_todo_called_where_shouldnt", "dp_todo", "todo"),
+    ("dont_panic_index_oob", "dont_panic", "index out of bounds"),
+    ("dont_panic_invalid_argument", "dont_panic", "invalid argument"),
+    ("dont_panic_overflow", "dont_panic", "arithmetic overflow"),
+    ("dont_panic_div_by_zero", "dont_panic", "division by zero"),
+    ("dont_panic_unwrap_none", "dont_panic", "unwrap on `None`"),
+    ("dont_panic_unwrap_result", "dont_panic", "unwrap on `Result`"),
+    ("dont_panic_assertion", "dp_assert", "assertion"),
+];
+
+/// Undefined symbol referenced by a `dont_panic!()` call.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    /// The raw name of the symbol.
+    pub name: String,
+    /// The name of the macro which referenced the symbol, e.g. `dont_panic`.
+    pub macro_name: &'static str,
+    /// The category of the problem, e.g. `index out of bounds`.
+    pub category: &'static str,
+    /// The location encoded in the name, if any.
+    ///
+    /// Symbols produced by older versions of `dont_panic` don't carry it.
+    pub location: Option<Location>,
+}
+
+/// The location of the `dont_panic!()` call as encoded in the symbol name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Location {
+    pub module: String,
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Symbol {
+    /// Decodes the symbol name, returns `None` if it wasn't produced by `dont_panic!()` or similar
+    /// macro.
+    ///
+    /// The name has the form `<prefix>$<module>$<file>:<line>:<column>`, e.g.
+    /// `rust_panic_called_where_shouldnt$my_crate$src/main.rs:3:5`.
+    pub fn parse(name: &str) -> Option<Self> {
+        // Mach-O prepends an underscore to C symbols.
+        let name = match name.strip_prefix('_') {
+            Some(unprefixed) if PREFIXES.iter().any(|&(prefix, _, _)| unprefixed.starts_with(prefix)) => unprefixed,
+            _ => name,
+        };
+
+        let (rest, macro_name, category) = PREFIXES.iter()
+            .find_map(|&(prefix, macro_name, category)| name.strip_prefix(prefix).map(|rest| (rest, macro_name, category)))?;
+        let location = if rest.is_empty() {
+            None
+        } else {
+            Some(Location::parse(rest.strip_prefix('$')?)?)
+        };
+
+        Some(Symbol {
+            name: name.to_owned(),
+            macro_name,
+            category,
+            location,
+        })
+    }
+}
+
+impl Location {
+    /// The id of the site records describing the call, `<module>$<file>:<line>:<column>`.
+    pub fn site_id(&self) -> String {
+        format!("{}${}:{}:{}", self.module, self.file, self.line, self.column)
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        let (module, position) = s.split_once('$')?;
+
+        // The file name may contain colons (e.g. on Windows), so split from the right.
+        let (position, column) = position.rsplit_once(':')?;
+        let (file, line) = position.rsplit_once(':')?;
+
+        Some(Location {
+            module: module.to_owned(),
+            file: file.to_owned(),
+            line: line.parse().ok()?,
+            column: column.parse().ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Location, Symbol};
+
+    #[test]
+    fn parse_with_location() {
+        let symbol = Symbol::parse("rust_panic_called_where_shouldnt$foo::bar$src/bar.rs:42:13").unwrap();
+        assert_eq!(symbol.location, Some(Location {
+            module: "foo::bar".to_owned(),
+            file: "src/bar.rs".to_owned(),
+            line: 42,
+            column: 13,
+        }));
+    }
+
+    #[test]
+    fn site_id() {
+        let symbol = Symbol::parse("dont_panic_overflow$foo::bar$src/bar.rs:42:13").unwrap();
+        assert_eq!(symbol.location.unwrap().site_id(), "foo::bar$src/bar.rs:42:13");
+    }
+
+    #[test]
+    fn parse_macro_name() {
+        let symbol = Symbol::parse("rust_panic_called_where_shouldnt$foo$src/lib.rs:1:2").unwrap();
+        assert_eq!(symbol.macro_name, "dont_panic");
+        let symbol = Symbol::parse("_rust_unreachable_called_where_shouldnt$foo$src/lib.rs:1:2").unwrap();
+        assert_eq!(symbol.macro_name, "dp_unreachable");
+        assert_eq!(symbol.name, "rust_unreachable_called_where_shouldnt$foo$src/lib.rs:1:2");
+    }
+
+    #[test]
+    fn parse_category() {
+        let symbol = Symbol::parse("rust_panic_called_where_shouldnt$foo$src/lib.rs:1:2").unwrap();
+        assert_eq!(symbol.category, "user-defined");
+        let symbol = Symbol::parse("dont_panic_index_oob$foo$src/lib.rs:1:2").unwrap();
+        assert_eq!(symbol.category, "index out of bounds");
+        assert_eq!(symbol.macro_name, "dont_panic");
+        let symbol = Symbol::parse("dont_panic_assertion$foo$src/lib.rs:1:2").unwrap();
+        assert_eq!(symbol.macro_name, "dp_assert");
+    }
+
+    #[test]
+    fn parse_windows_path() {
+        let symbol = Symbol::parse("rust_panic_called_where_shouldnt$foo$C:\\foo\\src\\lib.rs:1:2").unwrap();
+        let location = symbol.location.unwrap();
+        assert_eq!(location.file, "C:\\foo\\src\\lib.rs");
+        assert_eq!(location.line, 1);
+        assert_eq!(location.column, 2);
+    }
+
+    #[test]
+    fn parse_mach_o() {
+        let symbol = Symbol::parse("_rust_panic_called_where_shouldnt$foo$src/lib.rs:1:2").unwrap();
+        assert_eq!(symbol.name, "rust_panic_called_where_shouldnt$foo$src/lib.rs:1:2");
+    }
+
+    #[test]
+    fn parse_legacy() {
+        let symbol = Symbol::parse("rust_panic_called_where_shouldnt").unwrap();
+        assert_eq!(symbol.location, None);
+    }
+
+    #[test]
+    fn parse_unrelated() {
+        assert_eq!(Symbol::parse("memcpy"), None);
+        assert_eq!(Symbol::parse("rust_panic_called_where_shouldnt_really"), None);
+        assert_eq!(Symbol::parse("rust_panic_called_where_shouldnt$foo$src/lib.rs:x:2"), None);
+    }
+}
@@ -0,0 +1,36 @@
+//! Source of `reachability.o`, the object file the tests of `scan.rs` run against.
+//!
+//! Build `dont_panic` with `cargo build --release` and regenerate the object from the root of the
+//! repository with:
+//!
+//! ```text
+//! rustc --edition 2018 --crate-name reachability --emit obj -C opt-level=2 -C debuginfo=1 \
+//!     -C codegen-units=1 -C panic=abort --remap-path-prefix=$PWD= \
+//!     --extern dont_panic=target/release/libdont_panic.rlib -L target/release/deps \
+//!     -o explain/tests/fixtures/reachability.o explain/tests/fixtures/reachability.rs
+//! ```
+
+#[macro_use]
+extern crate dont_panic;
+
+#[inline(always)]
+fn check(value: usize) -> usize {
+    if value == 42 {
+        dont_panic!("the answer is {}", value);
+    }
+    value
+}
+
+/// Not called from `main`, so the linker would discard it along with the call.
+#[no_mangle]
+#[inline(never)]
+pub extern "C" fn unused(value: usize) -> usize {
+    if value == 7 {
+        dp_unreachable!();
+    }
+    value
+}
+
+fn main() {
+    std::process::exit(check(std::env::args().count()) as i32);
+}
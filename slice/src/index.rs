@@ -0,0 +1,129 @@
+//! Indexing `DPSlice` by positions and ranges.
+
+use core::ops::{Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+use DPSlice;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Types which can index `DPSlice`, like `core::slice::SliceIndex` for slices.
+///
+/// Implemented for `usize`, which returns a single element, and for ranges, which return a
+/// subslice. `index()` and `index_mut()` call `dont_panic!()` if the index is out of bounds.
+///
+/// This trait is sealed, it can't be implemented outside of this crate.
+pub trait DPSliceIndex<T>: private::Sealed {
+    /// The type returned by indexing.
+    type Output: ?Sized;
+
+    /// Returns the output, or `None` if the index is out of bounds.
+    fn get(self, slice: &DPSlice<T>) -> Option<&Self::Output>;
+
+    /// Returns the mutable output, or `None` if the index is out of bounds.
+    fn get_mut(self, slice: &mut DPSlice<T>) -> Option<&mut Self::Output>;
+
+    /// Returns the output, calls `dont_panic!()` if the index is out of bounds.
+    fn index(self, slice: &DPSlice<T>) -> &Self::Output;
+
+    /// Returns the mutable output, calls `dont_panic!()` if the index is out of bounds.
+    fn index_mut(self, slice: &mut DPSlice<T>) -> &mut Self::Output;
+}
+
+impl private::Sealed for usize {}
+
+impl<T> DPSliceIndex<T> for usize {
+    type Output = T;
+
+    #[inline(always)]
+    fn get(self, slice: &DPSlice<T>) -> Option<&T> {
+        DPSlice::as_rust_slice(slice).get(self)
+    }
+
+    #[inline(always)]
+    fn get_mut(self, slice: &mut DPSlice<T>) -> Option<&mut T> {
+        DPSlice::as_rust_slice_mut(slice).get_mut(self)
+    }
+
+    #[inline(always)]
+    fn index(self, slice: &DPSlice<T>) -> &T {
+        let len = slice.len();
+        self.get(slice).unwrap_or_else(|| __dont_panic_category!(index_oob; "index out of bounds: the len is {} but the index is {}", len, self))
+    }
+
+    #[inline(always)]
+    fn index_mut(self, slice: &mut DPSlice<T>) -> &mut T {
+        let len = slice.len();
+        self.get_mut(slice).unwrap_or_else(|| __dont_panic_category!(index_oob; "index out of bounds: the len is {} but the index is {}", len, self))
+    }
+}
+
+/// Converts the range to `start..end`, calling `dont_panic!()` if it's out of bounds.
+#[inline(always)]
+pub(crate) fn slice_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => match start.checked_add(1) {
+            Some(start) => start,
+            None => __dont_panic_category!(index_oob; "attempted to index slice from after maximum usize"),
+        },
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => match end.checked_add(1) {
+            Some(end) => end,
+            None => __dont_panic_category!(index_oob; "attempted to index slice up to maximum usize"),
+        },
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if start > end {
+        if let Bound::Unbounded = range.end_bound() {
+            __dont_panic_category!(index_oob; "range start index {} out of range for slice of length {}", start, len);
+        }
+        __dont_panic_category!(index_oob; "slice index starts at {} but ends at {}", start, end);
+    }
+
+    if end > len {
+        __dont_panic_category!(index_oob; "range end index {} out of range for slice of length {}", end, len);
+    }
+
+    start..end
+}
+
+macro_rules! range_index {
+    ($($range:ty),*) => {
+        $(
+            impl private::Sealed for $range {}
+
+            impl<T> DPSliceIndex<T> for $range {
+                type Output = DPSlice<T>;
+
+                #[inline(always)]
+                fn get(self, slice: &DPSlice<T>) -> Option<&DPSlice<T>> {
+                    DPSlice::as_rust_slice(slice).get(self).map(Into::into)
+                }
+
+                #[inline(always)]
+                fn get_mut(self, slice: &mut DPSlice<T>) -> Option<&mut DPSlice<T>> {
+                    DPSlice::as_rust_slice_mut(slice).get_mut(self).map(Into::into)
+                }
+
+                #[inline(always)]
+                fn index(self, slice: &DPSlice<T>) -> &DPSlice<T> {
+                    let range = slice_range(self, slice.len());
+                    unsafe { DPSlice::as_rust_slice(slice).get_unchecked(range).into() }
+                }
+
+                #[inline(always)]
+                fn index_mut(self, slice: &mut DPSlice<T>) -> &mut DPSlice<T> {
+                    let range = slice_range(self, slice.len());
+                    unsafe { DPSlice::as_rust_slice_mut(slice).get_unchecked_mut(range).into() }
+                }
+            }
+        )*
+    };
+}
+
+range_index!(Range<usize>, RangeFrom<usize>, RangeTo<usize>, RangeToInclusive<usize>, RangeInclusive<usize>, RangeFull, (Bound<usize>, Bound<usize>));
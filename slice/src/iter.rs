@@ -0,0 +1,240 @@
+//! Iterators over subslices of `DPSlice`, yielding `DPSlice`s.
+//!
+//! These wrap the iterators from `core::slice`, so the items stay checked.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::slice;
+use DPSlice;
+
+/// Implements the iterator traits by converting the items of the wrapped iterator.
+///
+/// `$pred` is the type of the predicate of splitting iterators, if any.
+macro_rules! iter_impls {
+    ($name:ident [$($pred:ident)*], $item:ty, $($extra:ident),*) => {
+        impl<'a, T $(, $pred: FnMut(&T) -> bool)*> Iterator for $name<'a, T $(, $pred)*> {
+            type Item = $item;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next().map(Into::into)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+
+            #[inline]
+            fn count(self) -> usize {
+                self.0.count()
+            }
+
+            #[inline]
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                self.0.nth(n).map(Into::into)
+            }
+
+            #[inline]
+            fn last(self) -> Option<Self::Item> {
+                self.0.last().map(Into::into)
+            }
+        }
+
+        impl<'a, T $(, $pred: FnMut(&T) -> bool)*> FusedIterator for $name<'a, T $(, $pred)*> {}
+
+        iter_impls!(@extras $name [$($pred)*] $($extra)*);
+    };
+    (@extras $name:ident $preds:tt $($extra:ident)*) => {
+        $(
+            iter_impls!(@$extra $name $preds);
+        )*
+    };
+    (@double_ended $name:ident [$($pred:ident)*]) => {
+        impl<'a, T $(, $pred: FnMut(&T) -> bool)*> DoubleEndedIterator for $name<'a, T $(, $pred)*> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.0.next_back().map(Into::into)
+            }
+
+            #[inline]
+            fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+                self.0.nth_back(n).map(Into::into)
+            }
+        }
+    };
+    (@clone $name:ident [$($pred:ident)*]) => {
+        impl<'a, T $(, $pred: Clone + FnMut(&T) -> bool)*> Clone for $name<'a, T $(, $pred)*> {
+            fn clone(&self) -> Self {
+                $name(self.0.clone())
+            }
+        }
+    };
+    (@exact_size $name:ident [$($pred:ident)*]) => {
+        impl<'a, T $(, $pred: FnMut(&T) -> bool)*> ExactSizeIterator for $name<'a, T $(, $pred)*> {}
+    };
+}
+
+/// Defines wrapper of iterator yielding shared subslices.
+macro_rules! shared_iter {
+    ($(#[$attr:meta])* $name:ident [$($pred:ident)*], $($extra:ident),*) => {
+        $(#[$attr])*
+        pub struct $name<'a, T: 'a $(, $pred: FnMut(&T) -> bool)*>(pub(crate) slice::$name<'a, T $(, $pred)*>);
+
+        impl<'a, T: fmt::Debug $(, $pred: FnMut(&T) -> bool)*> fmt::Debug for $name<'a, T $(, $pred)*> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.0).finish()
+            }
+        }
+
+        iter_impls!($name [$($pred)*], &'a DPSlice<T>, $($extra),*);
+    };
+}
+
+/// Defines wrapper of iterator yielding mutable subslices.
+macro_rules! mut_iter {
+    ($(#[$attr:meta])* $name:ident [$($pred:ident)*], $($extra:ident),*) => {
+        $(#[$attr])*
+        pub struct $name<'a, T: 'a $(, $pred: FnMut(&T) -> bool)*>(pub(crate) slice::$name<'a, T $(, $pred)*>);
+
+        impl<'a, T: fmt::Debug $(, $pred: FnMut(&T) -> bool)*> fmt::Debug for $name<'a, T $(, $pred)*> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.0).finish()
+            }
+        }
+
+        iter_impls!($name [$($pred)*], &'a mut DPSlice<T>, $($extra),*);
+    };
+}
+
+shared_iter!(
+    /// Iterator over overlapping windows, returned by `DPSlice::windows()`.
+    Windows [], clone, double_ended, exact_size
+);
+
+shared_iter!(
+    /// Iterator over chunks, returned by `DPSlice::chunks()`.
+    Chunks [], clone, double_ended, exact_size
+);
+
+mut_iter!(
+    /// Iterator over mutable chunks, returned by `DPSlice::chunks_mut()`.
+    ChunksMut [], double_ended, exact_size
+);
+
+shared_iter!(
+    /// Iterator over chunks of exactly the given size, returned by `DPSlice::chunks_exact()`.
+    ChunksExact [], clone, double_ended, exact_size
+);
+
+mut_iter!(
+    /// Iterator over mutable chunks of exactly the given size, returned by
+    /// `DPSlice::chunks_exact_mut()`.
+    ChunksExactMut [], double_ended, exact_size
+);
+
+shared_iter!(
+    /// Iterator over chunks starting at the end, returned by `DPSlice::rchunks()`.
+    RChunks [], clone, double_ended, exact_size
+);
+
+mut_iter!(
+    /// Iterator over mutable chunks starting at the end, returned by `DPSlice::rchunks_mut()`.
+    RChunksMut [], double_ended, exact_size
+);
+
+shared_iter!(
+    /// Iterator over chunks of exactly the given size starting at the end, returned by
+    /// `DPSlice::rchunks_exact()`.
+    RChunksExact [], clone, double_ended, exact_size
+);
+
+mut_iter!(
+    /// Iterator over mutable chunks of exactly the given size starting at the end, returned by
+    /// `DPSlice::rchunks_exact_mut()`.
+    RChunksExactMut [], double_ended, exact_size
+);
+
+shared_iter!(
+    /// Iterator over subslices separated by elements matching the predicate, returned by
+    /// `DPSlice::split()`.
+    Split [P], clone, double_ended
+);
+
+mut_iter!(
+    /// Iterator over mutable subslices separated by elements matching the predicate, returned by
+    /// `DPSlice::split_mut()`.
+    SplitMut [P], double_ended
+);
+
+shared_iter!(
+    /// Like `Split`, but starting at the end, returned by `DPSlice::rsplit()`.
+    RSplit [P], clone, double_ended
+);
+
+mut_iter!(
+    /// Like `SplitMut`, but starting at the end, returned by `DPSlice::rsplit_mut()`.
+    RSplitMut [P], double_ended
+);
+
+shared_iter!(
+    /// Like `Split`, but limited to the given number of items, returned by `DPSlice::splitn()`.
+    SplitN [P],
+);
+
+mut_iter!(
+    /// Like `SplitMut`, but limited to the given number of items, returned by
+    /// `DPSlice::splitn_mut()`.
+    SplitNMut [P],
+);
+
+shared_iter!(
+    /// Like `RSplit`, but limited to the given number of items, returned by `DPSlice::rsplitn()`.
+    RSplitN [P],
+);
+
+mut_iter!(
+    /// Like `RSplitMut`, but limited to the given number of items, returned by
+    /// `DPSlice::rsplitn_mut()`.
+    RSplitNMut [P],
+);
+
+shared_iter!(
+    /// Like `Split`, but the matching elements are included at the end of the subslices,
+    /// returned by `DPSlice::split_inclusive()`.
+    SplitInclusive [P], clone, double_ended
+);
+
+mut_iter!(
+    /// Like `SplitMut`, but the matching elements are included at the end of the subslices,
+    /// returned by `DPSlice::split_inclusive_mut()`.
+    SplitInclusiveMut [P], double_ended
+);
+
+impl<'a, T> ChunksExact<'a, T> {
+    /// Returns the elements which don't fit into the chunks.
+    pub fn remainder(&self) -> &'a DPSlice<T> {
+        self.0.remainder().into()
+    }
+}
+
+impl<'a, T> ChunksExactMut<'a, T> {
+    /// Returns the elements which don't fit into the chunks.
+    pub fn into_remainder(self) -> &'a mut DPSlice<T> {
+        self.0.into_remainder().into()
+    }
+}
+
+impl<'a, T> RChunksExact<'a, T> {
+    /// Returns the elements which don't fit into the chunks.
+    pub fn remainder(&self) -> &'a DPSlice<T> {
+        self.0.remainder().into()
+    }
+}
+
+impl<'a, T> RChunksExactMut<'a, T> {
+    /// Returns the elements which don't fit into the chunks.
+    pub fn into_remainder(self) -> &'a mut DPSlice<T> {
+        self.0.into_remainder().into()
+    }
+}
@@ -1,5 +1,6 @@
 //! Non-panicking drop-in replacement for slices. Instead of panic it causes link time error if
-//! bounds are not checked. (Not fully drop-in replacement yet. Some features are missing.)
+//! bounds are not checked. The methods of `[T]` which could panic call `dont_panic!()` if their
+//! preconditions don't hold, the methods returning subslices return `DPSlice`s.
 //!
 //! # Example
 //!
@@ -14,6 +15,8 @@
 //!     let dps = <&DPSlice<_>>::from(&arr as &[_]);
 //!     assert_eq!(dps[0], 0);
 //!     assert_eq!(dps[3], 3);
+//!     // Slicing returns DPSlice too
+//!     assert_eq!(dps[1..][0], 1);
 //!     // This would not compile (instead of run time panicking)
 //!     assert_eq!(dps[42], 42);
 //! }
@@ -27,6 +30,15 @@
 #[macro_use]
 extern crate dont_panic;
 
+mod index;
+pub mod iter;
+
+pub use index::DPSliceIndex;
+pub use iter::{Chunks, ChunksExact, ChunksExactMut, ChunksMut, RChunks, RChunksExact, RChunksExactMut, RChunksMut, RSplit, RSplitMut, RSplitN, RSplitNMut, Split, SplitInclusive, SplitInclusiveMut, SplitMut, SplitN, SplitNMut, Windows};
+
+use core::cmp::Ordering;
+use core::ops::{Index, IndexMut, RangeBounds};
+
 pub struct DPSlice<T>([T]);
 
 impl<T> DPSlice<T> {
@@ -46,6 +58,16 @@ impl<T> DPSlice<T> {
         Self::as_rust_slice(self).is_empty()
     }
 
+    /// Returns the element or subslice, or `None` if the index is out of bounds.
+    pub fn get<I: DPSliceIndex<T>>(&self, index: I) -> Option<&I::Output> {
+        index.get(self)
+    }
+
+    /// Returns the mutable element or subslice, or `None` if the index is out of bounds.
+    pub fn get_mut<I: DPSliceIndex<T>>(&mut self, index: I) -> Option<&mut I::Output> {
+        index.get_mut(self)
+    }
+
     pub fn first(&self) -> Option<&T> {
         Self::as_rust_slice(self).first()
     }
@@ -54,66 +76,312 @@ impl<T> DPSlice<T> {
         Self::as_rust_slice_mut(self).first_mut()
     }
 
-    pub fn split_first(&self) -> Option<(&T, &[T])> {
-        Self::as_rust_slice(self).split_first()
+    pub fn split_first(&self) -> Option<(&T, &DPSlice<T>)> {
+        Self::as_rust_slice(self).split_first().map(|(first, rest)| (first, rest.into()))
     }
 
-    pub fn split_first_mut(&self) -> Option<(&T, &[T])> {
-        Self::as_rust_slice(self).split_first()
+    pub fn split_first_mut(&mut self) -> Option<(&mut T, &mut DPSlice<T>)> {
+        Self::as_rust_slice_mut(self).split_first_mut().map(|(first, rest)| (first, rest.into()))
     }
 
-    pub fn split_last(&self) -> Option<(&T, &[T])> {
-        Self::as_rust_slice(self).split_last()
+    pub fn split_last(&self) -> Option<(&T, &DPSlice<T>)> {
+        Self::as_rust_slice(self).split_last().map(|(last, rest)| (last, rest.into()))
     }
 
-    pub fn split_last_mut(&mut self) -> Option<(&T, &[T])> {
-        Self::as_rust_slice_mut(self).split_last()
+    pub fn split_last_mut(&mut self) -> Option<(&mut T, &mut DPSlice<T>)> {
+        Self::as_rust_slice_mut(self).split_last_mut().map(|(last, rest)| (last, rest.into()))
     }
 
     pub fn swap(&mut self, a: usize, b: usize) {
-        if a > self.len() {
-            dont_panic!("index out of bounds: the len is {} but the index is {}", self.len(), a);
+        if a >= self.len() {
+            __dont_panic_category!(index_oob; "index out of bounds: the len is {} but the index is {}", self.len(), a);
         }
 
-        if b > self.len() {
-            dont_panic!("index out of bounds: the len is {} but the index is {}", self.len(), b);
+        if b >= self.len() {
+            __dont_panic_category!(index_oob; "index out of bounds: the len is {} but the index is {}", self.len(), b);
         }
 
         Self::as_rust_slice_mut(self).swap(a, b);
     }
 
-    pub fn windows(&self, size: usize) -> ::core::slice::Windows<T> {
-        dp_assert!(size != 0);
+    pub fn windows(&self, size: usize) -> Windows<'_, T> {
+        if size == 0 {
+            __dont_panic_category!(invalid_argument; "window size must be non-zero");
+        }
 
-        Self::as_rust_slice(self).windows(size)
+        Windows(Self::as_rust_slice(self).windows(size))
     }
 
-    pub fn chunks(&self, size: usize) -> ::core::slice::Chunks<T> {
-        dp_assert!(size != 0);
+    pub fn chunks(&self, size: usize) -> Chunks<'_, T> {
+        check_chunk_size(size);
+        Chunks(Self::as_rust_slice(self).chunks(size))
+    }
 
-        Self::as_rust_slice(self).chunks(size)
+    pub fn chunks_mut(&mut self, size: usize) -> ChunksMut<'_, T> {
+        check_chunk_size(size);
+        ChunksMut(Self::as_rust_slice_mut(self).chunks_mut(size))
     }
 
-    pub fn chunks_mut(&mut self, size: usize) -> ::core::slice::ChunksMut<T> {
-        dp_assert!(size != 0);
+    pub fn split_at(&self, mid: usize) -> (&DPSlice<T>, &DPSlice<T>) {
+        if mid > self.len() {
+            __dont_panic_category!(index_oob; "index {} out of range for slice of length {}", mid, self.len());
+        }
 
-        Self::as_rust_slice_mut(self).chunks_mut(size)
+        let (left, right) = Self::as_rust_slice(self).split_at(mid);
+        (left.into(), right.into())
     }
 
-    pub fn split_at(&self, mid: usize) -> (&[T], &[T]) {
+    pub fn split_at_mut(&mut self, mid: usize) -> (&mut DPSlice<T>, &mut DPSlice<T>) {
         if mid > self.len() {
-            dont_panic!("index {} out of range for slice of length {}", mid, self.len());
+            __dont_panic_category!(index_oob; "index {} out of range for slice of length {}", mid, self.len());
         }
 
-        Self::as_rust_slice(self).split_at(mid)
+        let (left, right) = Self::as_rust_slice_mut(self).split_at_mut(mid);
+        (left.into(), right.into())
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        Self::as_rust_slice(self).last()
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        Self::as_rust_slice_mut(self).last_mut()
+    }
+
+    pub fn iter(&self) -> ::core::slice::Iter<'_, T> {
+        Self::as_rust_slice(self).iter()
+    }
+
+    pub fn iter_mut(&mut self) -> ::core::slice::IterMut<'_, T> {
+        Self::as_rust_slice_mut(self).iter_mut()
     }
 
-    pub fn split_at_mut(&mut self, mid: usize) -> (&mut [T], &mut [T]) {
+    pub fn reverse(&mut self) {
+        Self::as_rust_slice_mut(self).reverse()
+    }
+
+    /// Calls `dont_panic!()` if `mid` is greater than the length.
+    pub fn rotate_left(&mut self, mid: usize) {
         if mid > self.len() {
-            dont_panic!("index {} out of range for slice of length {}", mid, self.len());
+            __dont_panic_category!(index_oob; "assertion failed: mid <= self.len()");
+        }
+
+        Self::as_rust_slice_mut(self).rotate_left(mid)
+    }
+
+    /// Calls `dont_panic!()` if `k` is greater than the length.
+    pub fn rotate_right(&mut self, k: usize) {
+        if k > self.len() {
+            __dont_panic_category!(index_oob; "assertion failed: k <= self.len()");
+        }
+
+        Self::as_rust_slice_mut(self).rotate_right(k)
+    }
+
+    pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
+        Self::as_rust_slice_mut(self).fill_with(f)
+    }
+
+    /// Calls `dont_panic!()` if the lengths differ.
+    pub fn swap_with_slice(&mut self, other: &mut [T]) {
+        if self.len() != other.len() {
+            __dont_panic_category!(invalid_argument; "destination and source slices have different lengths");
+        }
+
+        Self::as_rust_slice_mut(self).swap_with_slice(other)
+    }
+
+    pub fn binary_search_by<F: FnMut(&T) -> Ordering>(&self, f: F) -> Result<usize, usize> {
+        Self::as_rust_slice(self).binary_search_by(f)
+    }
+
+    pub fn binary_search_by_key<B: Ord, F: FnMut(&T) -> B>(&self, key: &B, f: F) -> Result<usize, usize> {
+        Self::as_rust_slice(self).binary_search_by_key(key, f)
+    }
+
+    pub fn partition_point<P: FnMut(&T) -> bool>(&self, pred: P) -> usize {
+        Self::as_rust_slice(self).partition_point(pred)
+    }
+
+    pub fn sort_unstable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: F) {
+        Self::as_rust_slice_mut(self).sort_unstable_by(compare)
+    }
+
+    pub fn sort_unstable_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        Self::as_rust_slice_mut(self).sort_unstable_by_key(f)
+    }
+
+    /// Calls `dont_panic!()` if `index` is out of bounds.
+    pub fn select_nth_unstable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, index: usize, compare: F) -> (&mut DPSlice<T>, &mut T, &mut DPSlice<T>) {
+        self.check_nth(index);
+        let (left, nth, right) = Self::as_rust_slice_mut(self).select_nth_unstable_by(index, compare);
+        (left.into(), nth, right.into())
+    }
+
+    /// Calls `dont_panic!()` if `index` is out of bounds.
+    pub fn select_nth_unstable_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, index: usize, f: F) -> (&mut DPSlice<T>, &mut T, &mut DPSlice<T>) {
+        self.check_nth(index);
+        let (left, nth, right) = Self::as_rust_slice_mut(self).select_nth_unstable_by_key(index, f);
+        (left.into(), nth, right.into())
+    }
+
+    #[inline(always)]
+    fn check_nth(&self, index: usize) {
+        if index >= self.len() {
+            __dont_panic_category!(index_oob; "partition_at_index index {} greater than length of slice {}", index, self.len());
         }
+    }
 
-        Self::as_rust_slice_mut(self).split_at_mut(mid)
+    pub fn split<P: FnMut(&T) -> bool>(&self, pred: P) -> Split<'_, T, P> {
+        Split(Self::as_rust_slice(self).split(pred))
+    }
+
+    pub fn split_mut<P: FnMut(&T) -> bool>(&mut self, pred: P) -> SplitMut<'_, T, P> {
+        SplitMut(Self::as_rust_slice_mut(self).split_mut(pred))
+    }
+
+    pub fn rsplit<P: FnMut(&T) -> bool>(&self, pred: P) -> RSplit<'_, T, P> {
+        RSplit(Self::as_rust_slice(self).rsplit(pred))
+    }
+
+    pub fn rsplit_mut<P: FnMut(&T) -> bool>(&mut self, pred: P) -> RSplitMut<'_, T, P> {
+        RSplitMut(Self::as_rust_slice_mut(self).rsplit_mut(pred))
+    }
+
+    pub fn splitn<P: FnMut(&T) -> bool>(&self, n: usize, pred: P) -> SplitN<'_, T, P> {
+        SplitN(Self::as_rust_slice(self).splitn(n, pred))
+    }
+
+    pub fn splitn_mut<P: FnMut(&T) -> bool>(&mut self, n: usize, pred: P) -> SplitNMut<'_, T, P> {
+        SplitNMut(Self::as_rust_slice_mut(self).splitn_mut(n, pred))
+    }
+
+    pub fn rsplitn<P: FnMut(&T) -> bool>(&self, n: usize, pred: P) -> RSplitN<'_, T, P> {
+        RSplitN(Self::as_rust_slice(self).rsplitn(n, pred))
+    }
+
+    pub fn rsplitn_mut<P: FnMut(&T) -> bool>(&mut self, n: usize, pred: P) -> RSplitNMut<'_, T, P> {
+        RSplitNMut(Self::as_rust_slice_mut(self).rsplitn_mut(n, pred))
+    }
+
+    pub fn split_inclusive<P: FnMut(&T) -> bool>(&self, pred: P) -> SplitInclusive<'_, T, P> {
+        SplitInclusive(Self::as_rust_slice(self).split_inclusive(pred))
+    }
+
+    pub fn split_inclusive_mut<P: FnMut(&T) -> bool>(&mut self, pred: P) -> SplitInclusiveMut<'_, T, P> {
+        SplitInclusiveMut(Self::as_rust_slice_mut(self).split_inclusive_mut(pred))
+    }
+
+    pub fn chunks_exact(&self, size: usize) -> ChunksExact<'_, T> {
+        check_chunk_size(size);
+        ChunksExact(Self::as_rust_slice(self).chunks_exact(size))
+    }
+
+    pub fn chunks_exact_mut(&mut self, size: usize) -> ChunksExactMut<'_, T> {
+        check_chunk_size(size);
+        ChunksExactMut(Self::as_rust_slice_mut(self).chunks_exact_mut(size))
+    }
+
+    pub fn rchunks(&self, size: usize) -> RChunks<'_, T> {
+        check_chunk_size(size);
+        RChunks(Self::as_rust_slice(self).rchunks(size))
+    }
+
+    pub fn rchunks_mut(&mut self, size: usize) -> RChunksMut<'_, T> {
+        check_chunk_size(size);
+        RChunksMut(Self::as_rust_slice_mut(self).rchunks_mut(size))
+    }
+
+    pub fn rchunks_exact(&self, size: usize) -> RChunksExact<'_, T> {
+        check_chunk_size(size);
+        RChunksExact(Self::as_rust_slice(self).rchunks_exact(size))
+    }
+
+    pub fn rchunks_exact_mut(&mut self, size: usize) -> RChunksExactMut<'_, T> {
+        check_chunk_size(size);
+        RChunksExactMut(Self::as_rust_slice_mut(self).rchunks_exact_mut(size))
+    }
+}
+
+impl<T: PartialEq> DPSlice<T> {
+    pub fn contains(&self, x: &T) -> bool {
+        Self::as_rust_slice(self).contains(x)
+    }
+
+    pub fn starts_with(&self, needle: &[T]) -> bool {
+        Self::as_rust_slice(self).starts_with(needle)
+    }
+
+    pub fn ends_with(&self, needle: &[T]) -> bool {
+        Self::as_rust_slice(self).ends_with(needle)
+    }
+
+    pub fn strip_prefix(&self, prefix: &[T]) -> Option<&DPSlice<T>> {
+        Self::as_rust_slice(self).strip_prefix(prefix).map(Into::into)
+    }
+
+    pub fn strip_suffix(&self, suffix: &[T]) -> Option<&DPSlice<T>> {
+        Self::as_rust_slice(self).strip_suffix(suffix).map(Into::into)
+    }
+}
+
+impl<T: Ord> DPSlice<T> {
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize> {
+        Self::as_rust_slice(self).binary_search(x)
+    }
+
+    pub fn sort_unstable(&mut self) {
+        Self::as_rust_slice_mut(self).sort_unstable()
+    }
+
+    /// Calls `dont_panic!()` if `index` is out of bounds.
+    pub fn select_nth_unstable(&mut self, index: usize) -> (&mut DPSlice<T>, &mut T, &mut DPSlice<T>) {
+        self.check_nth(index);
+        let (left, nth, right) = Self::as_rust_slice_mut(self).select_nth_unstable(index);
+        (left.into(), nth, right.into())
+    }
+}
+
+impl<T: Clone> DPSlice<T> {
+    pub fn fill(&mut self, value: T) {
+        Self::as_rust_slice_mut(self).fill(value)
+    }
+
+    /// Calls `dont_panic!()` if the lengths differ.
+    pub fn clone_from_slice(&mut self, src: &[T]) {
+        if self.len() != src.len() {
+            __dont_panic_category!(invalid_argument; "destination and source slices have different lengths");
+        }
+
+        Self::as_rust_slice_mut(self).clone_from_slice(src)
+    }
+}
+
+impl<T: Copy> DPSlice<T> {
+    /// Calls `dont_panic!()` if the lengths differ.
+    pub fn copy_from_slice(&mut self, src: &[T]) {
+        if self.len() != src.len() {
+            __dont_panic_category!(invalid_argument; "source slice length ({}) does not match destination slice length ({})", src.len(), self.len());
+        }
+
+        Self::as_rust_slice_mut(self).copy_from_slice(src)
+    }
+
+    /// Calls `dont_panic!()` if `src` is out of bounds or the copy doesn't fit at `dest`.
+    pub fn copy_within<R: RangeBounds<usize>>(&mut self, src: R, dest: usize) {
+        let src = index::slice_range(src, self.len());
+        if dest > self.len() - (src.end - src.start) {
+            __dont_panic_category!(index_oob; "dest is out of bounds");
+        }
+
+        Self::as_rust_slice_mut(self).copy_within(src, dest)
+    }
+}
+
+#[inline(always)]
+fn check_chunk_size(size: usize) {
+    if size == 0 {
+        __dont_panic_category!(invalid_argument; "chunk size must be non-zero");
     }
 }
 
@@ -145,19 +413,19 @@ impl<'a, T> From<&'a mut DPSlice<T>> for &'a mut [T] {
 }
 */
 
-impl<T> ::core::ops::Index<usize> for DPSlice<T> {
-    type Output = T;
+impl<T, I: DPSliceIndex<T>> Index<I> for DPSlice<T> {
+    type Output = I::Output;
 
     #[inline(always)]
-    fn index(&self, index: usize) -> &Self::Output {
-        Self::as_rust_slice(self).get(index).unwrap_or_else(|| dont_panic!("index out of bounds: the len is {} but the index is {}", self.len(), index))
+    fn index(&self, index: I) -> &Self::Output {
+        index.index(self)
     }
 }
 
-impl<T> ::core::ops::IndexMut<usize> for DPSlice<T> {
+impl<T, I: DPSliceIndex<T>> IndexMut<I> for DPSlice<T> {
     #[inline(always)]
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        Self::as_rust_slice_mut(self).get_mut(index).unwrap_or_else(|| dont_panic!("index out of bounds: the len is {} but the index is {}", self.len(), index))
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        index.index_mut(self)
     }
 }
 
@@ -183,6 +451,126 @@ mod tests {
         assert_eq!(dps[3], 3);
     }
 
+    #[test]
+    fn range() {
+        let mut arr = [0, 1, 2, 3];
+        let dps = <&mut DPSlice<_>>::from(&mut arr as &mut [_]);
+        assert_eq!(DPSlice::as_rust_slice(&dps[1..3]), &[1, 2]);
+        assert_eq!(DPSlice::as_rust_slice(&dps[1..]), &[1, 2, 3]);
+        assert_eq!(DPSlice::as_rust_slice(&dps[..3]), &[0, 1, 2]);
+        assert_eq!(DPSlice::as_rust_slice(&dps[..=3]), &[0, 1, 2, 3]);
+        assert_eq!(DPSlice::as_rust_slice(&dps[1..=1]), &[1]);
+        assert_eq!(DPSlice::as_rust_slice(&dps[..]), &[0, 1, 2, 3]);
+        assert!(dps[4..].is_empty());
+        dps[2..][0] = 42;
+        assert_eq!(dps[2], 42);
+    }
+
+    #[test]
+    fn split() {
+        let mut arr = [0, 1, 2, 3, 4];
+        let dps = <&mut DPSlice<_>>::from(&mut arr as &mut [_]);
+        let (first, rest) = dps.split_first().unwrap();
+        assert_eq!((*first, rest[0]), (0, 1));
+        let (last, rest) = dps.split_last().unwrap();
+        assert_eq!((*last, rest.len()), (4, 4));
+        let (left, right) = dps.split_at(2);
+        assert_eq!((left.len(), right[0]), (2, 2));
+        let (first, rest) = dps.split_first_mut().unwrap();
+        *first = rest[0];
+        let (last, rest) = dps.split_last_mut().unwrap();
+        rest[1] = *last;
+        assert_eq!(DPSlice::as_rust_slice(dps), &[1, 4, 2, 3, 4]);
+        let (left, right) = dps.split_at_mut(2);
+        left[0] = right[0];
+        assert_eq!(dps[0], 2);
+        let windows = dps.windows(2).map(|window| window[0] + window[1]);
+        assert!(windows.eq([6, 6, 5, 7].iter().cloned()));
+        assert!(dps.chunks(2).map(DPSlice::len).eq([2, 2, 1].iter().cloned()));
+        assert_eq!(dps.chunks(2).next_back().map(|chunk| chunk[0]), Some(4));
+        for chunk in dps.chunks_mut(2) {
+            chunk[0] = 42;
+        }
+        assert_eq!(DPSlice::as_rust_slice(dps), &[42, 4, 42, 3, 42]);
+    }
+
+    #[test]
+    fn api() {
+        let mut arr = [3, 0, 2, 1, 0];
+        let dps = <&mut DPSlice<_>>::from(&mut arr as &mut [_]);
+        assert_eq!((dps.first(), dps.last()), (Some(&3), Some(&0)));
+        assert!(dps.contains(&2) && !dps.contains(&4));
+        assert!(dps.starts_with(&[3, 0]) && dps.ends_with(&[1, 0]));
+        assert!(dps.split(|&x| x == 0).map(DPSlice::len).eq([1, 2, 0].iter().cloned()));
+        assert!(dps.rsplitn(2, |&x| x == 0).map(DPSlice::len).eq([0, 4].iter().cloned()));
+        assert_eq!(dps.split_inclusive(|&x| x == 2).next().map(DPSlice::len), Some(3));
+        assert_eq!(dps.chunks_exact(2).remainder().len(), 1);
+        assert_eq!(dps.rchunks(2).next().map(|chunk| chunk[0]), Some(1));
+        assert_eq!(dps.strip_prefix(&[3]).map(DPSlice::len), Some(4));
+
+        dps.rotate_left(1);
+        assert_eq!(DPSlice::as_rust_slice(dps), &[0, 2, 1, 0, 3]);
+        dps.rotate_right(1);
+        dps.reverse();
+        assert_eq!(DPSlice::as_rust_slice(dps), &[0, 1, 2, 0, 3]);
+        dps.copy_within(3.., 0);
+        assert_eq!(DPSlice::as_rust_slice(dps), &[0, 3, 2, 0, 3]);
+        let (_, nth, _) = dps.select_nth_unstable(4);
+        assert_eq!(*nth, 3);
+        dps.sort_unstable();
+        assert_eq!(dps.binary_search(&2), Ok(2));
+        dps[..2].copy_from_slice(&[7, 8]);
+        dps[2..4].swap_with_slice(&mut [5, 6]);
+        assert_eq!(DPSlice::as_rust_slice(dps), &[7, 8, 5, 6, 3]);
+        dps.fill(1);
+        assert!(dps.iter().all(|&x| x == 1));
+    }
+
+    #[cfg(feature = "panic")]
+    #[test]
+    #[should_panic(expected = "dest is out of bounds")]
+    fn copy_within_out_of_bounds() {
+        let mut arr = [0, 1, 2, 3];
+        let dps = <&mut DPSlice<_>>::from(&mut arr as &mut [_]);
+        dps.copy_within(1..3, ::core::hint::black_box(3));
+    }
+
+    #[test]
+    fn get() {
+        use core::ops::Bound;
+
+        let mut arr = [0, 1, 2, 3];
+        let dps = <&mut DPSlice<_>>::from(&mut arr as &mut [_]);
+        assert_eq!(dps.get(3), Some(&3));
+        assert_eq!(dps.get(4), None);
+        assert_eq!(dps.get(1..3).map(DPSlice::as_rust_slice), Some(&[1, 2][..]));
+        assert!(dps.get(::core::hint::black_box(3)..2).is_none());
+        assert!(dps.get(..=4).is_none());
+        assert_eq!(dps.get((Bound::Excluded(0), Bound::Included(1))).map(DPSlice::as_rust_slice), Some(&[1][..]));
+        *dps.get_mut(0).unwrap() = 42;
+        assert_eq!(DPSlice::as_rust_slice(&dps[(Bound::Unbounded, Bound::Excluded(1))]), &[42]);
+    }
+
+    #[cfg(feature = "panic")]
+    #[test]
+    #[should_panic(expected = "slice index starts at 3 but ends at 2")]
+    fn range_start_after_end() {
+        let arr = [0, 1, 2, 3];
+        let dps = <&DPSlice<_>>::from(&arr as &[_]);
+        let start = ::core::hint::black_box(3);
+        let _ = &dps[start..2];
+    }
+
+    #[cfg(feature = "panic")]
+    #[test]
+    #[should_panic(expected = "range end index 5 out of range for slice of length 4")]
+    fn range_end_out_of_bounds() {
+        let arr = [0, 1, 2, 3];
+        let dps = <&DPSlice<_>>::from(&arr as &[_]);
+        let end = ::core::hint::black_box(4);
+        let _ = &dps[..=end];
+    }
+
     #[cfg(feature = "panic")]
     #[test]
     fn no_panic() {
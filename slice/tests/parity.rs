@@ -0,0 +1,170 @@
+//! Checks that the preconditions of `DPSlice` methods are exactly as strong as the panic
+//! conditions of the corresponding `[T]` methods, by trying all small lengths and arguments.
+//!
+//! `DPSlice` methods must panic only through `dont_panic!()`, never by passing invalid arguments
+//! on to `[T]` methods, which the hook of `dont_panic` checks.
+
+// Run using `cargo test --features panic`, without selecting another mode of `dont_panic`.
+#![cfg(feature = "panic")]
+
+extern crate dont_panic;
+extern crate dont_panic_slice;
+
+use dont_panic::hook::{self, SiteInfo};
+use dont_panic_slice::DPSlice;
+use std::cell::Cell;
+use std::ops::Bound;
+use std::panic::{self, AssertUnwindSafe};
+
+const MAX_LEN: usize = 5;
+const MAX_ARG: usize = MAX_LEN + 2;
+
+thread_local! {
+    static DONT_PANIC_CALLED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn record(_info: &SiteInfo) {
+    DONT_PANIC_CALLED.with(|called| called.set(true));
+}
+
+fn panics<F: FnOnce()>(f: F) -> bool {
+    panic::catch_unwind(AssertUnwindSafe(f)).is_err()
+}
+
+/// Runs the operation on a slice and `DPSlice` of the given length, checking both of them panic or
+/// neither does and they end up with the same contents.
+fn check<F: FnOnce(&mut [usize]), G: FnOnce(&mut DPSlice<usize>)>(len: usize, core: F, dp: G, what: &str) {
+    let mut core_arr = (0..len).collect::<Vec<_>>();
+    let mut dp_arr = core_arr.clone();
+    let core_panicked = panics(|| core(&mut core_arr));
+    hook::set_hook(record);
+    DONT_PANIC_CALLED.with(|called| called.set(false));
+    let dp_panicked = panics(|| dp(<&mut DPSlice<_>>::from(&mut *dp_arr)));
+    assert_eq!(dp_panicked, core_panicked, "{}", what);
+    assert_eq!(DONT_PANIC_CALLED.with(Cell::get), dp_panicked, "{} didn't panic through dont_panic!()", what);
+    assert_eq!(dp_arr, core_arr, "{}", what);
+}
+
+fn bound(kind: usize, value: usize) -> Bound<usize> {
+    match kind {
+        0 => Bound::Included(value),
+        1 => Bound::Excluded(value),
+        _ => Bound::Unbounded,
+    }
+}
+
+/// Runs `$body` with `$s` being both `[usize]` and `DPSlice<usize>` of all lengths up to
+/// `MAX_LEN` and all the arguments up to `MAX_ARG`.
+macro_rules! parity {
+    ($s:ident $(, $arg:ident)* => $body:expr) => {
+        for len in 0..=MAX_LEN {
+            parity!(@loop len, $s, [$($arg)*], [$($arg)*] => $body);
+        }
+    };
+    (@loop $len:ident, $s:ident, [], [$($all:ident)*] => $body:expr) => {
+        let what = format!("`{}` with length {} and arguments {:?}", stringify!($body), $len, ($($all,)*));
+        check($len, |$s: &mut [usize]| { let _ = $body; }, |$s: &mut DPSlice<usize>| { let _ = $body; }, &what);
+    };
+    (@loop $len:ident, $s:ident, [$arg:ident $($rest:ident)*], $all:tt => $body:expr) => {
+        for $arg in 0..=MAX_ARG {
+            parity!(@loop $len, $s, [$($rest)*], $all => $body);
+        }
+    };
+}
+
+#[test]
+fn index() {
+    parity!(s, i => s[i]);
+    parity!(s, i => s[i] = 42);
+}
+
+#[test]
+fn index_range() {
+    parity!(s, a, b => &s[a..b]);
+    parity!(s, a, b => &s[a..=b]);
+    parity!(s, a => &s[a..]);
+    parity!(s, b => &s[..b]);
+    parity!(s, b => &s[..=b]);
+    parity!(s => &s[..]);
+    parity!(s, a, b => &mut s[a..b]);
+    parity!(s, a, b => &mut s[a..=b]);
+}
+
+#[test]
+fn index_bounds() {
+    parity!(s, start, end, a, b => &s[(bound(start % 3, a), bound(end % 3, b))]);
+}
+
+#[test]
+fn index_max() {
+    parity!(s => &s[..=usize::MAX]);
+    parity!(s => &s[(Bound::Excluded(usize::MAX), Bound::Unbounded)]);
+    parity!(s => &s[usize::MAX..]);
+}
+
+#[test]
+fn get() {
+    for len in 0..=MAX_LEN {
+        let arr = (0..len).collect::<Vec<_>>();
+        let dps = <&DPSlice<_>>::from(&*arr);
+        for a in 0..=MAX_ARG {
+            assert_eq!(dps.get(a), arr.get(a));
+            for b in 0..=MAX_ARG {
+                assert_eq!(dps.get(a..b).map(DPSlice::as_rust_slice), arr.get(a..b));
+                assert_eq!(dps.get(a..=b).map(DPSlice::as_rust_slice), arr.get(a..=b));
+            }
+        }
+    }
+    parity!(s, i => s.get_mut(i).map(|x| *x = 42));
+}
+
+#[test]
+fn swap() {
+    parity!(s, a, b => s.swap(a, b));
+}
+
+#[test]
+fn split_at() {
+    parity!(s, mid => s.split_at(mid));
+    parity!(s, mid => s.split_at_mut(mid).0.reverse());
+}
+
+#[test]
+fn split_first_last() {
+    parity!(s => s.split_first_mut().map(|(first, rest)| rest.fill(*first)));
+    parity!(s => s.split_last_mut().map(|(last, rest)| rest.fill(*last)));
+}
+
+#[test]
+fn chunks() {
+    parity!(s, size => s.windows(size).count());
+    parity!(s, size => s.chunks(size).count());
+    parity!(s, size => s.chunks_mut(size).for_each(|chunk| chunk.reverse()));
+    parity!(s, size => s.chunks_exact(size).count());
+    parity!(s, size => s.chunks_exact_mut(size).for_each(|chunk| chunk.reverse()));
+    parity!(s, size => s.rchunks(size).count());
+    parity!(s, size => s.rchunks_mut(size).for_each(|chunk| chunk.reverse()));
+    parity!(s, size => s.rchunks_exact(size).count());
+    parity!(s, size => s.rchunks_exact_mut(size).for_each(|chunk| chunk.reverse()));
+}
+
+#[test]
+fn rotate() {
+    parity!(s, mid => s.rotate_left(mid));
+    parity!(s, k => s.rotate_right(k));
+}
+
+#[test]
+fn select_nth() {
+    parity!(s, index => s.select_nth_unstable(index));
+    parity!(s, index => s.select_nth_unstable_by(index, |a, b| b.cmp(a)));
+    parity!(s, index => s.select_nth_unstable_by_key(index, |&x| x % 2));
+}
+
+#[test]
+fn copy() {
+    parity!(s, a, b, dest => s.copy_within(a..b, dest));
+    parity!(s, n => s.copy_from_slice(&[42; MAX_ARG][..n]));
+    parity!(s, n => s.clone_from_slice(&[42; MAX_ARG][..n]));
+    parity!(s, n => s.swap_with_slice(&mut [42; MAX_ARG][..n]));
+}
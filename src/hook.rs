@@ -0,0 +1,94 @@
+//! Hook called before panicking in `panic` and `abort` modes.
+//!
+//! This makes it possible to tell the "impossible" paths apart from ordinary panics, e.g. in a
+//! fuzzing harness:
+//!
+//! ```no_compile
+//! use dont_panic::hook::{self, SiteInfo};
+//!
+//! fn record(info: &SiteInfo) {
+//!     // Store info.id() somewhere, log info.message()...
+//! #   let _ = info;
+//! }
+//!
+//! hook::set_hook(record);
+//! ```
+//!
+//! The hook is stored in a `static`, so it works in `no_std` too. In other modes the hook is
+//! never called.
+
+use core::fmt;
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Information about the `dont_panic!()` call which is about to panic.
+#[derive(Debug, Copy, Clone)]
+pub struct SiteInfo<'a> {
+    id: &'static str,
+    macro_name: &'static str,
+    file: &'static str,
+    line: u32,
+    column: u32,
+    message: fmt::Arguments<'a>,
+}
+
+impl<'a> SiteInfo<'a> {
+    /// Identifier of the call, in the form `<module path>$<file>:<line>:<column>`.
+    ///
+    /// This is the same identifier the site record and the name of the missing symbol in `link`
+    /// mode use. Calls expanded from a single macro invocation share it.
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    /// Name of the macro which was called, e.g. `dont_panic` or `dp_unreachable`.
+    pub fn macro_name(&self) -> &'static str {
+        self.macro_name
+    }
+
+    /// The file containing the call.
+    pub fn file(&self) -> &'static str {
+        self.file
+    }
+
+    /// The line of the call.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The column of the call.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// The message the panic is going to have.
+    pub fn message(&self) -> fmt::Arguments<'a> {
+        self.message
+    }
+}
+
+/// Registers the hook, replacing the previous one.
+pub fn set_hook(hook: fn(&SiteInfo)) {
+    HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+/// Unregisters the hook.
+pub fn reset_hook() {
+    HOOK.store(ptr::null_mut(), Ordering::Release);
+}
+
+/// Calls the hook, if any.
+#[doc(hidden)]
+#[cold]
+pub fn call_hook(id: &'static str, macro_name: &'static str, file: &'static str, line: u32, column: u32, message: fmt::Arguments) {
+    let hook = HOOK.load(Ordering::Acquire);
+    if hook.is_null() {
+        return;
+    }
+    // Only `set_hook()` stores non-null pointers and it stores `fn(&SiteInfo)`.
+    let hook = unsafe { mem::transmute::<*mut (), fn(&SiteInfo)>(hook) };
+    hook(&SiteInfo { id, macro_name, file, line, column, message });
+}
@@ -23,35 +23,562 @@
 //! ```
 //!
 //! Compile with `--release` or `--features=panic`
+//!
+//! # Modes
+//!
+//! What `dont_panic!()` (and the other macros of this crate) does depends on the selected mode:
+//!
+//! * `link` - causes a linking error if not optimized-out (default)
+//! * `panic` - panics, like `panic!()`
+//! * `abort` - panics without unwinding, aborting the program
+//! * `trap` - executes an illegal instruction, without formatting the message or even touching
+//!   the panic machinery (x86, ARM, RISC-V and LoongArch only)
+//! * `unchecked` - tells the optimizer the call is unreachable, see below
+//! * `auto` - `panic` if debug assertions are on (debug builds), `link` otherwise (release builds)
+//!
+//! The `panic` feature selects `panic` mode. Since features are additive and any crate in the
+//! dependency graph may turn it on, the mode can also be selected explicitly, overriding the
+//! feature, using `DONT_PANIC_MODE` environment variable or `dont_panic_mode` cfg:
+//!
+//! ```text
+//! DONT_PANIC_MODE=link cargo build --release
+//! RUSTFLAGS='--cfg dont_panic_mode="panic"' cargo test
+//! ```
+//!
+//! This way CI can verify the code using linking errors, while developers keep panics, without
+//! touching Cargo features. `auto` mode can be selected using `auto` feature too, so that consumers
+//! of a library using `dont_panic` don't have to remember turning on `panic` feature in debug
+//! builds. (`panic` feature takes precedence.)
+//!
+//! Note that `auto` mode is resolved when `dont_panic` itself is compiled, according to its own
+//! debug assertions setting, not `cfg(debug_assertions)` of the crate calling `dont_panic!()`. These
+//! only differ if the profile is overridden for some of the crates.
+//!
+//! In `panic` and `abort` modes, the hook registered using `hook::set_hook()` is called before
+//! panicking, so the calls can be told apart from ordinary panics.
+//!
+//! Since `dont_panic!()` calls are practically never optimized-out with `opt-level = 0`, in release
+//! builds without optimizations in `link` mode `dont_panic!()` and the other macros fail with a
+//! compile error explaining this, instead of a confusing linking error. Only the crates calling them
+//! fail, so build scripts and proc macros, which are built with `opt-level = 0` even in release
+//! builds, can depend on crates using `dont_panic` internally.
+//!
+//! `trap` mode is intended for production builds which can't afford the link-time checks on every
+//! build, e.g. firmware. It has to be selected explicitly, overriding the `panic` feature:
+//!
+//! ```text
+//! DONT_PANIC_MODE=trap cargo build --release
+//! ```
+//!
+//! ## Unchecked mode
+//!
+//! Once the code was verified in `link` mode, the calls can be turned into
+//! `core::hint::unreachable_unchecked()`, so the optimizer can exploit the proven facts (e.g. when
+//! optimizing the code around the call, which it could only do partially before). Since this is
+//! undefined behavior if the call is actually reachable, the mode can't be selected using the
+//! environment variable or the features, only using an explicitly named cfg:
+//!
+//! ```text
+//! RUSTFLAGS='--cfg dont_panic_unsafe_assume_unreachable' cargo build --release
+//! ```
+//!
+//! This is sound only if a build in `link` mode succeeded with exactly the same code, dependencies,
+//! compiler, target, profile and flags (except for the cfg above). A successful link proves the
+//! optimizer has removed every call, which it only does if it proved the call unreachable for
+//! every execution of the program. Beware that the proof is only as good as the program is: if the
+//! program has undefined behavior elsewhere, the optimizer may have removed the call based on it.
+//! Anything differing between the builds, even an innocent-looking compiler upgrade, invalidates
+//! the proof, so the link-mode build should be part of the same pipeline producing the unchecked
+//! build.
+//!
+//! `abort` mode requires Rust 1.81 or newer, which aborts when unwinding out of `extern "C"`
+//! functions.
+//!
+//! # Finding the offending call
+//!
+//! Every `dont_panic!()` expansion references its own undefined symbol, so the linker error
+//! tells you which call wasn't optimized-out. The name of the symbol has the form
+//! `rust_panic_called_where_shouldnt$<module path>$<file>:<line>:<column>`, for example:
+//!
+//! ```text
+//! undefined symbol: rust_panic_called_where_shouldnt$my_crate::parser$src/parser.rs:42:13
+//! ```
+//!
+//! `dp_unreachable!()`, `dp_unimplemented!()` and `dp_todo!()` use `rust_unreachable_...`,
+//! `rust_unimplemented_...` and `rust_todo_...` prefixes respectively.
+//!
+//! The checks performed by this crate (and the related crates) use prefixes telling the category
+//! of the problem:
+//!
+//! | Prefix                        | Category                                               |
+//! |-------------------------------|--------------------------------------------------------|
+//! | `dont_panic_index_oob`        | index out of bounds                                    |
+//! | `dont_panic_invalid_argument` | other invalid argument, e.g. zero chunk size           |
+//! | `dont_panic_overflow`         | arithmetic overflow                                    |
+//! | `dont_panic_div_by_zero`      | division by zero                                       |
+//! | `dont_panic_unwrap_none`      | `dp_unwrap()` or `dp_expect()` on `None`               |
+//! | `dont_panic_unwrap_result`    | `dp_unwrap()`, `dp_expect()` and friends on `Result`   |
+//! | `dont_panic_assertion`        | failed `dp_assert!()`, `dp_assert_eq!()` or `dp_assert_ne!()` |
+//!
+//! # Site inventory
+//!
+//! On ELF targets, every `dont_panic!()` call also stores a record describing it in the object
+//! file. The `.dont_panic_sites` section contains pointers to the records, each of them consisting of
+//! these NUL-terminated strings:
+//!
+//! 1. site id - `<module path>$<file>:<line>:<column>`, the same as the end of the symbol name
+//! 2. the file
+//! 3. the line
+//! 4. the column
+//! 5. the arguments of the macro as written in the source code
+//! 6. the name of the macro (e.g. `dont_panic`)
+//! 7. the symbol prefix (e.g. `dont_panic_overflow`)
+//!
+//! The site id is not unique: all calls expanded from a single macro invocation (e.g. a
+//! `macro_rules!` generating several functions) share it. Such calls still have distinct records.
+//!
+//! The section is marked as excluded, so the linker discards it along with the records and they
+//! don't end up in the final binary. They are intended for tools like `dont-panic-explain`.
+//!
+//! # Panic strategies
+//!
+//! `dont_panic!()` and `dp_assert!()` work the same way regardless of the panic strategy - the
+//! call has to be optimized-out.
+//!
+//! `call()` relies on a guard which calls `dont_panic!()` when dropped during unwinding. With
+//! `panic = "abort"` nothing is unwound, so the guard is optimized-out even if the closure can
+//! panic and `call()` doesn't check anything. In `no_std` binaries, use `panic_handler!()` instead.
+//! It defines `#[panic_handler]` calling `dont_panic!()`, so the binary links only if no panic is
+//! reachable from anywhere in it, including the dependencies. There's no replacement for `std`
+//! binaries compiled with `panic = "abort"`, since `std` provides its own panic handler.
+//!
+//! Alternatively, turn on the `handler` feature and the crate defines the panic handler itself.
+//! The feature works only on `no_std` targets, any crate linking `std` (including the tests of this
+//! crate) would end up with two panic handlers.
+//! `dont_panic!()` calls still reference their own symbols, so the linking error names their
+//! location. Panics coming from `core` (e.g. out of bounds indexing) only make the handler
+//! reachable; `dont-panic-explain` lists the places calling into `core` panicking functions.
+//!
+//! | Strategy | `dont_panic!()` | `call()`       | `panic_handler!()` |
+//! |----------|-----------------|----------------|--------------------|
+//! | unwind   | checked         | checked        | `no_std` only      |
+//! | abort    | checked         | **unchecked**  | `no_std` only      |
 
 #![no_std]
 
+use core::fmt;
+
 extern "C" {
     /// This function doesn't actually exist. It ensures a linking error if it isn't optimized-out.
     pub fn rust_panic_called_where_shouldnt() -> !;
 }
 
+#[doc(hidden)]
+pub mod __private {
+    pub use core::arch::global_asm;
+    pub use core::hint::{spin_loop, unreachable_unchecked};
+    pub use core::panic;
+    pub use core::panic::PanicInfo;
+    pub use num::{Arith, ArithError, ArithNeg};
+    pub use hook::call_hook;
+
+    /// Calls the closure, aborting if it panics.
+    ///
+    /// Unwinding out of `extern "C"` function aborts.
+    #[cfg(dont_panic_impl = "abort")]
+    #[cold]
+    #[inline(never)]
+    pub extern "C" fn abort<F: FnOnce()>(f: F) -> ! {
+        f();
+        unreachable!("the closure always panics")
+    }
+
+    /// Executes an illegal instruction.
+    #[cfg(dont_panic_impl = "trap")]
+    #[inline(always)]
+    pub fn trap() -> ! {
+        unsafe {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            core::arch::asm!("ud2", options(noreturn, nomem, nostack));
+            #[cfg(target_arch = "aarch64")]
+            core::arch::asm!("brk #0x1", options(noreturn, nomem, nostack));
+            #[cfg(target_arch = "arm")]
+            core::arch::asm!("udf #0xfe", options(noreturn, nomem, nostack));
+            #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+            core::arch::asm!("unimp", options(noreturn, nomem, nostack));
+            #[cfg(target_arch = "loongarch64")]
+            core::arch::asm!("break 0", options(noreturn, nomem, nostack));
+        }
+    }
+
+    #[cfg(all(
+        dont_panic_impl = "trap",
+        not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv32", target_arch = "riscv64", target_arch = "loongarch64")),
+    ))]
+    compile_error!("`trap` mode of dont_panic is not supported on this architecture");
+
+    /// Accepts the same single-argument messages as the panicking modes, which format them using
+    /// `Display`.
+    #[inline(always)]
+    pub fn check_message<T: ::core::fmt::Display + ?Sized>(_message: &T) {}
+
+    /// Suffix `module_path!()` has inside the module generated by `__dont_panic_site_record!()`.
+    pub const SITE_MODULE_SUFFIX: &str = "::dont_panic_site";
+
+    /// Converts the site record to an array, so it can be stored in a `static`.
+    ///
+    /// The record is `module` without `SITE_MODULE_SUFFIX` followed by `rest`.
+    pub const fn site_record<const N: usize>(module: &str, rest: &str) -> [u8; N] {
+        let module = module.as_bytes();
+        let rest = rest.as_bytes();
+        let module_len = N - rest.len();
+        let mut result = [0; N];
+        let mut i = 0;
+        while i < module_len {
+            result[i] = module[i];
+            i += 1;
+        }
+        while i < N {
+            result[i] = rest[i - module_len];
+            i += 1;
+        }
+        result
+    }
+
+    /// Calls `dont_panic!()` when dropped, `#[dont_panic]` forgets it on every non-panicking exit.
+    pub struct Guard;
+
+    #[cfg(not(dont_panic_impl = "panic"))]
+    impl Drop for Guard {
+        #[inline(always)]
+        fn drop(&mut self) {
+            ::__dont_panic_fail!(@internal "rust_panic_called_where_shouldnt", "dont_panic", panic; "panic in #[dont_panic] function");
+        }
+    }
+
+    /// The panic itself is reported in `panic` mode, the guard only has to be forgettable.
+    #[cfg(dont_panic_impl = "panic")]
+    impl Drop for Guard {
+        #[inline(always)]
+        fn drop(&mut self) {}
+    }
+}
+
+/// Type-checks the arguments the way `panic!()` would, without evaluating them.
+///
+/// This makes sure `dont_panic!()` accepts the same arguments in all modes.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_check_args {
+    () => ();
+    // A literal is a format string, like in `panic!()`, even without arguments.
+    ($msg:literal $(,)?) => (
+        if false {
+            let _ = format_args!($msg);
+        }
+    );
+    ($msg:expr $(,)*) => (
+        if false {
+            $crate::__private::check_message(&$msg);
+        }
+    );
+    ($fmt:expr, $($arg:tt)+) => (
+        if false {
+            let _ = format_args!($fmt, $($arg)+);
+        }
+    );
+}
+
+/// Stores the record describing the site in the `.dont_panic_sites` section.
+///
+/// The section is marked as excluded, so the linker drops it and consequently the records, which
+/// are only referenced from the section.
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv32", target_arch = "riscv64", target_arch = "loongarch64"),
+    not(any(target_vendor = "apple", windows, target_os = "uefi")),
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_site_record {
+    ($prefix:tt, $name:tt; $($x:tt)*) => (
+        // global_asm!() is not allowed in statement position
+        mod dont_panic_site {
+            // The module path of the call, followed by `SITE_MODULE_SUFFIX`.
+            const MODULE: &str = module_path!();
+            const RECORD: &str = concat!(
+                "$", file!(), ":", line!(), ":", column!(), "\0",
+                file!(), "\0", line!(), "\0", column!(), "\0", stringify!($($x)*), "\0", $name, "\0", $prefix, "\0"
+            );
+
+            static RECORD_BYTES: [u8; MODULE.len() - $crate::__private::SITE_MODULE_SUFFIX.len() + RECORD.len()] = $crate::__private::site_record(MODULE, RECORD);
+
+            #[cfg(target_pointer_width = "64")]
+            $crate::__private::global_asm!(".pushsection .dont_panic_sites,\"e\"", ".quad {}", ".popsection", sym RECORD_BYTES);
+            #[cfg(target_pointer_width = "32")]
+            $crate::__private::global_asm!(".pushsection .dont_panic_sites,\"e\"", ".long {}", ".popsection", sym RECORD_BYTES);
+        }
+    )
+}
+
+/// Site records are only supported on ELF targets with stable `global_asm!()`.
+#[cfg(not(all(
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv32", target_arch = "riscv64", target_arch = "loongarch64"),
+    not(any(target_vendor = "apple", windows, target_os = "uefi")),
+)))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_site_record {
+    ($prefix:tt, $name:tt; $($x:tt)*) => ();
+}
+
+/// Calls the hook registered using `hook::set_hook()`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_call_hook {
+    ($name:tt, $message:expr) => (
+        $crate::__private::call_hook(concat!(module_path!(), "$", file!(), ":", line!(), ":", column!()), $name, file!(), line!(), column!(), $message)
+    )
+}
+
+/// Formats the message `$panic!()` would have, so the hook can see it.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_message {
+    (panic;) => (format_args!("explicit panic"));
+    (unreachable;) => (format_args!("internal error: entered unreachable code"));
+    (unimplemented;) => (format_args!("not implemented"));
+    (todo;) => (format_args!("not yet implemented"));
+    (panic; $msg:literal $(,)?) => (format_args!($msg));
+    (unreachable; $msg:literal $(,)?) => (format_args!("internal error: entered unreachable code: {}", format_args!($msg)));
+    (unimplemented; $msg:literal $(,)?) => (format_args!("not implemented: {}", format_args!($msg)));
+    (todo; $msg:literal $(,)?) => (format_args!("not yet implemented: {}", format_args!($msg)));
+    (panic; $msg:expr $(,)*) => (format_args!("{}", $msg));
+    (unreachable; $msg:expr $(,)*) => (format_args!("internal error: entered unreachable code: {}", $msg));
+    (unimplemented; $msg:expr $(,)*) => (format_args!("not implemented: {}", $msg));
+    (todo; $msg:expr $(,)*) => (format_args!("not yet implemented: {}", $msg));
+    (panic; $fmt:expr, $($arg:tt)+) => (format_args!($fmt, $($arg)+));
+    (unreachable; $fmt:expr, $($arg:tt)+) => (format_args!("internal error: entered unreachable code: {}", format_args!($fmt, $($arg)+)));
+    (unimplemented; $fmt:expr, $($arg:tt)+) => (format_args!("not implemented: {}", format_args!($fmt, $($arg)+)));
+    (todo; $fmt:expr, $($arg:tt)+) => (format_args!("not yet implemented: {}", format_args!($fmt, $($arg)+)));
+}
+
+/// Calls a non-existing function with name starting with `$prefix`, on behalf of the macro called
+/// `$name`.
+#[cfg(dont_panic_impl = "link")]
+#[doc(hidden)]
+#[macro_export]
+///
+/// The calls inside this crate pass `@internal`, so that they don't fail its build without
+/// optimizations.
+macro_rules! __dont_panic_fail {
+    (@internal $prefix:literal, $name:literal, $panic:ident; $($x:tt)*) => ({
+        $crate::__dont_panic_check_args!($($x)*);
+
+        $crate::__dont_panic_site_record!($prefix, $name; $($x)*);
+
+        extern "C" {
+            #[link_name = concat!($prefix, "$", module_path!(), "$", file!(), ":", line!(), ":", column!())]
+            fn rust_panic_called_where_shouldnt() -> !;
+        }
+
+        unsafe { rust_panic_called_where_shouldnt(); }
+    });
+    ($prefix:literal, $name:literal, $panic:ident; $($x:tt)*) => ({
+        $crate::__dont_panic_check_optimized!();
+        $crate::__dont_panic_fail!(@internal $prefix, $name, $panic; $($x)*)
+    });
+}
+
+/// Fails the build, since the calls are not optimized-out with `opt-level = 0` anyway.
+#[cfg(dont_panic_unoptimized)]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_check_optimized {
+    () => (compile_error!("dont_panic!() calls are not optimized-out with opt-level = 0, enable optimizations or select `panic` mode (e.g. using DONT_PANIC_MODE=panic)"));
+}
+
+#[cfg(not(dont_panic_unoptimized))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_check_optimized {
+    () => ();
+}
+
+/// Panics with the message `$panic!()` would have, after calling the hook.
+#[cfg(dont_panic_impl = "panic")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_fail {
+    ($(@$internal:ident)? $prefix:literal, $name:literal, $panic:ident; $($x:tt)*) => (
+        match $crate::__dont_panic_message!($panic; $($x)*) {
+            message => {
+                $crate::__dont_panic_call_hook!($name, message);
+                $crate::__private::panic!("{}", message)
+            },
+        }
+    )
+}
+
+/// Panics with the message `$panic!()` would have without unwinding, after calling the hook.
+#[cfg(dont_panic_impl = "abort")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_fail {
+    ($(@$internal:ident)? $prefix:literal, $name:literal, $panic:ident; $($x:tt)*) => (
+        match $crate::__dont_panic_message!($panic; $($x)*) {
+            message => {
+                $crate::__dont_panic_call_hook!($name, message);
+                $crate::__private::abort(|| $crate::__private::panic!("{}", message))
+            },
+        }
+    )
+}
+
+/// Executes an illegal instruction, type-checking the arguments only.
+#[cfg(dont_panic_impl = "trap")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_fail {
+    ($(@$internal:ident)? $prefix:literal, $name:literal, $panic:ident; $($x:tt)*) => ({
+        $crate::__dont_panic_check_args!($($x)*);
+        $crate::__private::trap()
+    })
+}
+
+/// Assumes the call is unreachable. See the crate documentation for the soundness requirements.
+#[cfg(dont_panic_impl = "unchecked")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_fail {
+    ($(@$internal:ident)? $prefix:literal, $name:literal, $panic:ident; $($x:tt)*) => ({
+        $crate::__dont_panic_check_args!($($x)*);
+        unsafe { $crate::__private::unreachable_unchecked() }
+    })
+}
+
+/// Calls `dont_panic!()` referencing symbol specific to the category of the problem.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dont_panic_category {
+    ($(@$internal:ident)? index_oob; $($x:tt)*) => ($crate::__dont_panic_fail!($(@$internal)? "dont_panic_index_oob", "dont_panic", panic; $($x)*));
+    ($(@$internal:ident)? invalid_argument; $($x:tt)*) => ($crate::__dont_panic_fail!($(@$internal)? "dont_panic_invalid_argument", "dont_panic", panic; $($x)*));
+    ($(@$internal:ident)? overflow; $($x:tt)*) => ($crate::__dont_panic_fail!($(@$internal)? "dont_panic_overflow", "dont_panic", panic; $($x)*));
+    ($(@$internal:ident)? div_by_zero; $($x:tt)*) => ($crate::__dont_panic_fail!($(@$internal)? "dont_panic_div_by_zero", "dont_panic", panic; $($x)*));
+    ($(@$internal:ident)? unwrap_none; $($x:tt)*) => ($crate::__dont_panic_fail!($(@$internal)? "dont_panic_unwrap_none", "dont_panic", panic; $($x)*));
+    ($(@$internal:ident)? unwrap_result; $($x:tt)*) => ($crate::__dont_panic_fail!($(@$internal)? "dont_panic_unwrap_result", "dont_panic", panic; $($x)*));
+    ($(@$internal:ident)? assertion; $($x:tt)*) => ($crate::__dont_panic_fail!($(@$internal)? "dont_panic_assertion", "dp_assert", panic; $($x)*));
+}
+
 /// This macro doesn't panic. Instead it tries to call a non-existing function. If the compiler can
 /// prove it can't be called and optimizes it away, the code will compile just fine. Otherwise you get
 /// a linking error.
 ///
+/// The name of the missing function encodes the module, file, line and column of the call, so the
+/// linking error points at the call which wasn't optimized-out.
+///
+/// The arguments are never evaluated but they are type-checked the same way in all modes, so the
+/// code compiles in `panic` mode too. They're either a format string literal with optional
+/// arguments, just like in `panic!()`, or a single non-literal value implementing `Display`.
+///
+/// In `panic` mode, it really panics instead of causing a linking error. The purpose is to make
+/// development easier. (E.g. in debug mode.) In `abort` mode, it panics without unwinding and in
+/// `trap` mode it executes an illegal instruction. See the crate documentation for how to select
+/// the mode.
+///
 /// This should be used only in cases you are absolutely sure are OK and optimizable by compiler.
-#[cfg(not(feature = "panic"))]
 #[macro_export]
 macro_rules! dont_panic {
-    ($($x:tt)*) => ({
-        unsafe { $crate::rust_panic_called_where_shouldnt(); }
-    })
+    ($($x:tt)*) => (
+        $crate::__dont_panic_fail!("rust_panic_called_where_shouldnt", "dont_panic", panic; $($x)*)
+    )
 }
 
-/// This macro is active only with `panic` feature turned on and it will really panic, instead of
-/// causing a linking error. The purpose is to make development easier. (E.g. in debug mode.)
-#[cfg(feature = "panic")]
+/// Like `unreachable!()` but causes a linking error instead of panicking, just like `dont_panic!()`.
+///
+/// The missing function is called `rust_unreachable_called_where_shouldnt$...`, so the linking
+/// error tells the kind of the call apart from `dont_panic!()`. It can be used in expression
+/// position, just like `unreachable!()`. In `panic` mode, this is just `unreachable!()`.
 #[macro_export]
-macro_rules! dont_panic {
-    ($($x:tt)*) => ({
-        panic!($($x)*);
-    })
+macro_rules! dp_unreachable {
+    ($($x:tt)*) => (
+        $crate::__dont_panic_fail!("rust_unreachable_called_where_shouldnt", "dp_unreachable", unreachable; $($x)*)
+    )
+}
+
+/// Like `unimplemented!()` but causes a linking error instead of panicking, just like
+/// `dont_panic!()`.
+///
+/// The missing function is called `rust_unimplemented_called_where_shouldnt$...`. In `panic`
+/// mode, this is just `unimplemented!()`.
+#[macro_export]
+macro_rules! dp_unimplemented {
+    ($($x:tt)*) => (
+        $crate::__dont_panic_fail!("rust_unimplemented_called_where_shouldnt", "dp_unimplemented", unimplemented; $($x)*)
+    )
+}
+
+/// Like `todo!()` but causes a linking error instead of panicking, just like `dont_panic!()`.
+///
+/// The missing function is called `rust_todo_called_where_shouldnt$...`. In `panic` mode, this is
+/// just `todo!()`.
+#[macro_export]
+macro_rules! dp_todo {
+    ($($x:tt)*) => (
+        $crate::__dont_panic_fail!("rust_todo_called_where_shouldnt", "dp_todo", todo; $($x)*)
+    )
+}
+
+/// Defines `#[panic_handler]` which calls `dont_panic!()`, ensuring no panic is reachable in the
+/// whole binary.
+///
+/// Unlike `call()`, this works with `panic = "abort"` too. It can be used only in `no_std`
+/// binaries, which don't get panic handler from `std`.
+///
+/// In other modes than `link`, the handler calls the given function instead, or just loops if
+/// there isn't any.
+///
+/// ```no_compile
+/// #![no_std]
+/// #![no_main]
+///
+/// #[macro_use]
+/// extern crate dont_panic;
+///
+/// panic_handler!(my_firmware::reset);
+/// ```
+#[cfg(dont_panic_impl = "link")]
+#[macro_export]
+macro_rules! panic_handler {
+    () => (
+        #[panic_handler]
+        fn dont_panic_handler(_info: &$crate::__private::PanicInfo) -> ! {
+            $crate::dont_panic!("panic handler reachable");
+        }
+    );
+    ($handler:path) => (
+        $crate::panic_handler!();
+    );
+}
+
+/// In other modes than `link`, the handler calls the given function, or just loops if there isn't
+/// any.
+#[cfg(not(dont_panic_impl = "link"))]
+#[macro_export]
+macro_rules! panic_handler {
+    () => (
+        #[panic_handler]
+        fn dont_panic_handler(_info: &$crate::__private::PanicInfo) -> ! {
+            loop {
+                $crate::__private::spin_loop();
+            }
+        }
+    );
+    ($handler:path) => (
+        #[panic_handler]
+        fn dont_panic_handler(info: &$crate::__private::PanicInfo) -> ! {
+            $handler(info)
+        }
+    );
 }
 
 /// Like assert but calls `dont_panic!()` instead of `panic!()`
@@ -59,25 +586,162 @@ macro_rules! dont_panic {
 macro_rules! dp_assert {
     ($cond:expr) => (
         if !$cond {
-            dont_panic!(concat!("assertion failed: ", stringify!($cond)))
+            $crate::__dont_panic_category!(assertion; concat!("assertion failed: ", stringify!($cond)))
         }
     );
 
     ($cond:expr, $($arg:tt)+) => (
         if !$cond {
-            dont_panic!($($arg)+)
+            $crate::__dont_panic_category!(assertion; $($arg)+)
+        }
+    );
+}
+
+/// Like `assert_eq!()` but calls `dont_panic!()` instead of `panic!()`
+#[macro_export]
+macro_rules! dp_assert_eq {
+    ($left:expr, $right:expr $(,)*) => (
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if !(*left_val == *right_val) {
+                    $crate::__dont_panic_category!(assertion; "assertion `left == right` failed\n  left: {:?}\n right: {:?}", left_val, right_val)
+                }
+            }
         }
     );
+
+    ($left:expr, $right:expr, $($arg:tt)+) => (
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if !(*left_val == *right_val) {
+                    $crate::__dont_panic_category!(assertion; "assertion `left == right` failed: {}\n  left: {:?}\n right: {:?}", format_args!($($arg)+), left_val, right_val)
+                }
+            }
+        }
+    );
+}
+
+/// Like `assert_ne!()` but calls `dont_panic!()` instead of `panic!()`
+#[macro_export]
+macro_rules! dp_assert_ne {
+    ($left:expr, $right:expr $(,)*) => (
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if *left_val == *right_val {
+                    $crate::__dont_panic_category!(assertion; "assertion `left != right` failed\n  left: {:?}\n right: {:?}", left_val, right_val)
+                }
+            }
+        }
+    );
+
+    ($left:expr, $right:expr, $($arg:tt)+) => (
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if *left_val == *right_val {
+                    $crate::__dont_panic_category!(assertion; "assertion `left != right` failed: {}\n  left: {:?}\n right: {:?}", format_args!($($arg)+), left_val, right_val)
+                }
+            }
+        }
+    );
+}
+
+pub mod num;
+pub mod hook;
+
+/// Extension trait providing non-panicking alternatives to `Option::unwrap()` and
+/// `Option::expect()`.
+///
+/// The methods call `dont_panic!()` if the value is `None`.
+pub trait DpOptionExt<T> {
+    /// Like `Option::unwrap()` but calls `dont_panic!()` instead of `panic!()`
+    fn dp_unwrap(self) -> T;
+
+    /// Like `Option::expect()` but calls `dont_panic!()` instead of `panic!()`
+    fn dp_expect(self, msg: &str) -> T;
+}
+
+impl<T> DpOptionExt<T> for Option<T> {
+    #[inline(always)]
+    fn dp_unwrap(self) -> T {
+        match self {
+            Some(value) => value,
+            None => __dont_panic_category!(@internal unwrap_none; "called `Option::dp_unwrap()` on a `None` value"),
+        }
+    }
+
+    #[inline(always)]
+    fn dp_expect(self, msg: &str) -> T {
+        match self {
+            Some(value) => value,
+            None => __dont_panic_category!(@internal unwrap_none; "{}", msg),
+        }
+    }
+}
+
+/// Extension trait providing non-panicking alternatives to `Result::unwrap()`,
+/// `Result::expect()`, `Result::unwrap_err()` and `Result::expect_err()`.
+///
+/// The methods call `dont_panic!()` if the value is of the unexpected variant.
+pub trait DpResultExt<T, E> {
+    /// Like `Result::unwrap()` but calls `dont_panic!()` instead of `panic!()`
+    fn dp_unwrap(self) -> T where E: fmt::Debug;
+
+    /// Like `Result::expect()` but calls `dont_panic!()` instead of `panic!()`
+    fn dp_expect(self, msg: &str) -> T where E: fmt::Debug;
+
+    /// Like `Result::unwrap_err()` but calls `dont_panic!()` instead of `panic!()`
+    fn dp_unwrap_err(self) -> E where T: fmt::Debug;
+
+    /// Like `Result::expect_err()` but calls `dont_panic!()` instead of `panic!()`
+    fn dp_expect_err(self, msg: &str) -> E where T: fmt::Debug;
+}
+
+impl<T, E> DpResultExt<T, E> for Result<T, E> {
+    #[inline(always)]
+    fn dp_unwrap(self) -> T where E: fmt::Debug {
+        match self {
+            Ok(value) => value,
+            Err(error) => __dont_panic_category!(@internal unwrap_result; "called `Result::dp_unwrap()` on an `Err` value: {:?}", error),
+        }
+    }
+
+    #[inline(always)]
+    fn dp_expect(self, msg: &str) -> T where E: fmt::Debug {
+        match self {
+            Ok(value) => value,
+            Err(error) => __dont_panic_category!(@internal unwrap_result; "{}: {:?}", msg, error),
+        }
+    }
+
+    #[inline(always)]
+    fn dp_unwrap_err(self) -> E where T: fmt::Debug {
+        match self {
+            Ok(value) => __dont_panic_category!(@internal unwrap_result; "called `Result::dp_unwrap_err()` on an `Ok` value: {:?}", value),
+            Err(error) => error,
+        }
+    }
+
+    #[inline(always)]
+    fn dp_expect_err(self, msg: &str) -> E where T: fmt::Debug {
+        match self {
+            Ok(value) => __dont_panic_category!(@internal unwrap_result; "{}: {:?}", msg, value),
+            Err(error) => error,
+        }
+    }
 }
 
 /// This function calls the given closure, asserting that there's no possibility of panicking.
 /// If the compiler can't prove this, the code will be left with a `dont_panic!` linking error.
-#[cfg(not(feature = "panic"))]
+///
+/// The check relies on unwinding, so it does nothing with `panic = "abort"`. See `panic_handler!()`
+/// for an alternative. In `abort` and `trap` modes, unwinding out of the closure aborts or traps
+/// respectively.
+#[cfg(not(dont_panic_impl = "panic"))]
 pub fn call<T, F: FnOnce() -> T>(f: F) -> T {
     struct DontPanic;
     impl Drop for DontPanic {
         fn drop(&mut self) {
-            dont_panic!();
+            __dont_panic_fail!(@internal "rust_panic_called_where_shouldnt", "dont_panic", panic;);
         }
     }
 
@@ -87,13 +751,17 @@ pub fn call<T, F: FnOnce() -> T>(f: F) -> T {
     result
 }
 
-/// With the `panic` feature turned on, this function just calls the closure directly, letting
+/// In `panic` mode, this function just calls the closure directly, letting
 /// it panic or not on its own.
-#[cfg(feature = "panic")]
+#[cfg(dont_panic_impl = "panic")]
 pub fn call<T, F: FnOnce() -> T>(f: F) -> T {
     f()
 }
 
+// Tests link `std`, which defines its own panic handler.
+#[cfg(all(feature = "handler", not(test)))]
+panic_handler!();
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -104,13 +772,121 @@ mod tests {
         }
     }
 
+    #[test]
+    fn multiple_sites() {
+        let should_panic = false;
+        if should_panic {
+            dont_panic!("first");
+        }
+        if should_panic {
+            dont_panic!("second");
+        }
+    }
+
+    #[test]
+    fn format_args() {
+        let should_panic = false;
+        let answer = 42;
+        if should_panic {
+            dont_panic!("the answer is {}", answer);
+        }
+        if should_panic {
+            dont_panic!("the answer is 42",);
+        }
+    }
+
+    #[test]
+    fn display_message() {
+        // Accepted in all modes, the message only has to implement `Display`.
+        let should_panic = false;
+        let message = "the answer";
+        let number = 42;
+        let owned = ::core::cell::Cell::new(42);
+        if should_panic {
+            dont_panic!(number);
+        }
+        if should_panic {
+            dont_panic!(message);
+        }
+        if should_panic {
+            dont_panic!(owned.get());
+        }
+        if should_panic {
+            dp_unreachable!(number);
+        }
+    }
+
+    #[test]
+    fn format_literal() {
+        // A single literal is a format string in all modes, like in `panic!()`.
+        let should_panic = false;
+        let x = 42;
+        if should_panic {
+            dont_panic!("x = {x} {{ok}}");
+        }
+        if should_panic {
+            dp_unreachable!("x = {x}",);
+        }
+    }
+
+    #[test]
+    fn assert_eq() {
+        let answer = 42;
+        dp_assert_eq!(answer, 42);
+        dp_assert_eq!(answer, 42, "the answer is {}", answer);
+        dp_assert_ne!(answer, 54);
+        dp_assert_ne!(answer, 54, "the answer is {}", answer);
+    }
+
+    #[test]
+    fn unreachable() {
+        fn check(x: Option<u32>) -> u32 {
+            match x {
+                Some(x) => x,
+                None => dp_unreachable!("x is always Some"),
+            }
+        }
+
+        fn never() -> ! {
+            dp_unreachable!()
+        }
+
+        let should_panic = false;
+        if should_panic {
+            never();
+        }
+        if should_panic {
+            dp_unimplemented!("the answer is {}", 42);
+        }
+        if should_panic {
+            dp_todo!();
+        }
+        assert_eq!(check(Some(42)), 42);
+    }
+
+    #[test]
+    fn unwrap() {
+        use super::{DpOptionExt, DpResultExt};
+
+        let arr = [1, 2, 3];
+        assert_eq!(*arr.first().dp_unwrap(), 1);
+        assert_eq!(*arr.last().dp_expect("arr is not empty"), 3);
+
+        let ok = Ok::<u32, ()>(42);
+        let err = Err::<(), u32>(42);
+        assert_eq!(ok.dp_unwrap(), 42);
+        assert_eq!(ok.dp_expect("ok is Ok"), 42);
+        assert_eq!(err.dp_unwrap_err(), 42);
+        assert_eq!(err.dp_expect_err("err is Err"), 42);
+    }
+
     #[test]
     fn call_slice_index() {
         let foo = [1, 2, 3];
         super::call(|| assert_eq!(foo[0] + foo[1] + foo[2], 6));
     }
 
-    #[cfg(feature = "panic")]
+    #[cfg(dont_panic_impl = "panic")]
     #[test]
     #[should_panic]
     fn panic() {
@@ -120,7 +896,7 @@ mod tests {
         }
     }
 
-    #[cfg(feature = "panic")]
+    #[cfg(dont_panic_impl = "panic")]
     #[test]
     fn no_panic() {
         let should_panic = false;
@@ -129,11 +905,105 @@ mod tests {
         }
     }
 
-    #[cfg(feature = "panic")]
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "x = 42 {ok}")]
+    fn panic_format_literal() {
+        let should_panic = ::core::hint::black_box(true);
+        let x = 42;
+        if should_panic {
+            dont_panic!("x = {x} {{ok}}");
+        }
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "internal error: entered unreachable code: x = 42")]
+    fn unreachable_format_literal() {
+        let should_panic = ::core::hint::black_box(true);
+        let x = 42;
+        if should_panic {
+            dp_unreachable!("x = {x}");
+        }
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "the answer is 42")]
+    fn panic_format_args() {
+        let should_panic = true;
+        let answer = 42;
+        if should_panic {
+            dont_panic!("the answer is {}", answer);
+        }
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
     #[test]
     #[should_panic]
     fn call_slice_index_panic() {
         let foo = [1, 2, 3];
-        super::call(|| assert_eq!(foo[1] + foo[2] + foo[3], 6));
+        let index = ::core::hint::black_box(3);
+        super::call(|| assert_eq!(foo[1] + foo[2] + foo[index], 6));
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "assertion `left == right` failed\n  left: 42\n right: 54")]
+    fn assert_eq_panic() {
+        let answer = ::core::hint::black_box(42);
+        dp_assert_eq!(answer, 54);
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "assertion `left != right` failed: the answer is 42\n  left: 42\n right: 42")]
+    fn assert_ne_panic() {
+        let answer = ::core::hint::black_box(42);
+        dp_assert_ne!(answer, 42, "the answer is {}", answer);
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "internal error: entered unreachable code: the answer is 42")]
+    fn unreachable_panic() {
+        fn check(x: Option<u32>) -> u32 {
+            match x {
+                Some(x) => x,
+                None => dp_unreachable!("the answer is {}", 42),
+            }
+        }
+
+        check(::core::hint::black_box(None));
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "not yet implemented")]
+    fn todo_panic() {
+        let should_panic = ::core::hint::black_box(true);
+        if should_panic {
+            dp_todo!();
+        }
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "called `Option::dp_unwrap()` on a `None` value")]
+    fn unwrap_panic() {
+        use super::DpOptionExt;
+
+        let arr: [u32; 0] = ::core::hint::black_box([]);
+        arr.first().dp_unwrap();
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "the answer: 42")]
+    fn expect_err_panic() {
+        use super::DpResultExt;
+
+        let answer = ::core::hint::black_box(Ok::<u32, ()>(42));
+        answer.dp_expect_err("the answer");
     }
 }
@@ -0,0 +1,564 @@
+//! Integer wrappers which call `dont_panic!()` instead of overflowing.
+//!
+//! Arithmetic on primitive integers panics on overflow in debug builds and wraps in release builds.
+//! The wrappers in this module check every operation and call `dont_panic!()` on overflow,
+//! division by zero or shifting by at least the number of bits of the type, so the code compiles
+//! only if the compiler can prove none of these happen.
+//!
+//! ```no_compile
+//! use dont_panic::num::DpUsize;
+//!
+//! fn middle(start: DpUsize, end: DpUsize) -> DpUsize {
+//!     // Links only if the compiler can prove `start <= end`.
+//!     start + (end - start) / DpUsize(2)
+//! }
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::Hash;
+use core::num::{NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign};
+
+macro_rules! dp_int {
+    ($name:ident, $int:ty) => {
+        /// Wrapper around the primitive integer which calls `dont_panic!()` instead of overflowing.
+        #[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        #[repr(transparent)]
+        pub struct $name(pub $int);
+
+        impl $name {
+            /// The smallest value that can be represented by this type.
+            pub const MIN: Self = $name(<$int>::MIN);
+
+            /// The largest value that can be represented by this type.
+            pub const MAX: Self = $name(<$int>::MAX);
+
+            /// Returns the wrapped integer.
+            #[inline(always)]
+            pub fn get(self) -> $int {
+                self.0
+            }
+        }
+
+        impl From<$int> for $name {
+            #[inline(always)]
+            fn from(value: $int) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<$name> for $int {
+            #[inline(always)]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                match self.0.checked_add(rhs.0) {
+                    Some(value) => $name(value),
+                    None => __dont_panic_category!(@internal overflow; "attempt to add with overflow"),
+                }
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self {
+                match self.0.checked_sub(rhs.0) {
+                    Some(value) => $name(value),
+                    None => __dont_panic_category!(@internal overflow; "attempt to subtract with overflow"),
+                }
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                match self.0.checked_mul(rhs.0) {
+                    Some(value) => $name(value),
+                    None => __dont_panic_category!(@internal overflow; "attempt to multiply with overflow"),
+                }
+            }
+        }
+
+        impl Div for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                if rhs.0 == 0 {
+                    __dont_panic_category!(@internal div_by_zero; "attempt to divide by zero");
+                }
+                match self.0.checked_div(rhs.0) {
+                    Some(value) => $name(value),
+                    None => __dont_panic_category!(@internal overflow; "attempt to divide with overflow"),
+                }
+            }
+        }
+
+        impl Rem for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn rem(self, rhs: Self) -> Self {
+                if rhs.0 == 0 {
+                    __dont_panic_category!(@internal div_by_zero; "attempt to calculate the remainder with a divisor of zero");
+                }
+                match self.0.checked_rem(rhs.0) {
+                    Some(value) => $name(value),
+                    None => __dont_panic_category!(@internal overflow; "attempt to calculate the remainder with overflow"),
+                }
+            }
+        }
+
+        impl Shl<u32> for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn shl(self, rhs: u32) -> Self {
+                match self.0.checked_shl(rhs) {
+                    Some(value) => $name(value),
+                    None => __dont_panic_category!(@internal overflow; "attempt to shift left with overflow"),
+                }
+            }
+        }
+
+        impl Shr<u32> for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn shr(self, rhs: u32) -> Self {
+                match self.0.checked_shr(rhs) {
+                    Some(value) => $name(value),
+                    None => __dont_panic_category!(@internal overflow; "attempt to shift right with overflow"),
+                }
+            }
+        }
+
+        impl Div<Divisor<$int>> for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Divisor<$int>) -> Self {
+                $name(self.0 / rhs)
+            }
+        }
+
+        impl Rem<Divisor<$int>> for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn rem(self, rhs: Divisor<$int>) -> Self {
+                $name(self.0 % rhs)
+            }
+        }
+
+        dp_int_assign!($name, AddAssign, add_assign, add, Self);
+        dp_int_assign!($name, SubAssign, sub_assign, sub, Self);
+        dp_int_assign!($name, MulAssign, mul_assign, mul, Self);
+        dp_int_assign!($name, DivAssign, div_assign, div, Self);
+        dp_int_assign!($name, RemAssign, rem_assign, rem, Self);
+        dp_int_assign!($name, ShlAssign, shl_assign, shl, u32);
+        dp_int_assign!($name, ShrAssign, shr_assign, shr, u32);
+        dp_int_assign!($name, DivAssign, div_assign, div, Divisor<$int>);
+        dp_int_assign!($name, RemAssign, rem_assign, rem, Divisor<$int>);
+    };
+}
+
+macro_rules! dp_int_assign {
+    ($name:ident, $trait:ident, $method:ident, $op:ident, $rhs:ty) => {
+        impl $trait<$rhs> for $name {
+            #[inline(always)]
+            fn $method(&mut self, rhs: $rhs) {
+                *self = self.$op(rhs);
+            }
+        }
+    };
+}
+
+macro_rules! dp_signed {
+    ($name:ident, $int:ty) => {
+        dp_int!($name, $int);
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self {
+                match self.0.checked_neg() {
+                    Some(value) => $name(value),
+                    None => __dont_panic_category!(@internal overflow; "attempt to negate with overflow"),
+                }
+            }
+        }
+    };
+}
+
+/// Divisor which can't cause division to panic.
+///
+/// The divisor is never zero. For signed types it's never `-1` either, since `MIN / -1`
+/// overflows; negate the dividend instead. Thanks to this, dividing primitive integers (and the
+/// wrappers in this module) by `Divisor` has no panicking branch at all, so there's nothing for the
+/// compiler to prove.
+///
+/// A `Divisor` can be obtained using `Divisor::new()`, which returns `None` for the invalid values,
+/// or `Divisor::dp_new()`, which calls `dont_panic!()` for them.
+///
+/// ```no_compile
+/// use dont_panic::num::Divisor;
+///
+/// fn bucket(hash: u64, buckets: Divisor<u64>) -> u64 {
+///     hash % buckets
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Divisor<T: DivisorInt>(T::NonZero);
+
+impl<T: DivisorInt> Divisor<T> {
+    /// Creates the divisor, returns `None` if the value is zero or `-1`.
+    #[inline(always)]
+    pub fn new(value: T) -> Option<Self> {
+        value.to_divisor().map(Divisor)
+    }
+
+    /// Creates the divisor, calls `dont_panic!()` if the value is zero or `-1`.
+    #[inline(always)]
+    pub fn dp_new(value: T) -> Self {
+        match value.to_divisor() {
+            Some(divisor) => Divisor(divisor),
+            None => __dont_panic_category!(@internal invalid_argument; "invalid divisor"),
+        }
+    }
+
+    /// Returns the value of the divisor.
+    #[inline(always)]
+    pub fn get(self) -> T {
+        T::from_divisor(self.0)
+    }
+}
+
+impl<T: DivisorInt> fmt::Display for Divisor<T> where T::NonZero: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Integer types which can be used as `Divisor`.
+///
+/// This trait is sealed, it's implemented for all primitive integer types.
+pub trait DivisorInt: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    type NonZero: Copy + fmt::Debug + Eq + Ord + Hash;
+
+    #[doc(hidden)]
+    fn to_divisor(self) -> Option<Self::NonZero>;
+
+    #[doc(hidden)]
+    fn from_divisor(divisor: Self::NonZero) -> Self;
+}
+
+macro_rules! divisor {
+    ($int:ty, $non_zero:ty, $is_valid:expr, |$lhs:ident, $rhs:ident| $div:expr, $rem:expr) => {
+        impl sealed::Sealed for $int {}
+
+        impl DivisorInt for $int {
+            type NonZero = $non_zero;
+
+            #[inline(always)]
+            fn to_divisor(self) -> Option<$non_zero> {
+                let is_valid: fn($int) -> bool = $is_valid;
+                if is_valid(self) {
+                    <$non_zero>::new(self)
+                } else {
+                    None
+                }
+            }
+
+            #[inline(always)]
+            fn from_divisor(divisor: $non_zero) -> Self {
+                divisor.get()
+            }
+        }
+
+        impl Div<Divisor<$int>> for $int {
+            type Output = $int;
+
+            #[inline(always)]
+            fn div(self, rhs: Divisor<$int>) -> $int {
+                let ($lhs, $rhs) = (self, rhs.0);
+                $div
+            }
+        }
+
+        impl Rem<Divisor<$int>> for $int {
+            type Output = $int;
+
+            #[inline(always)]
+            fn rem(self, rhs: Divisor<$int>) -> $int {
+                let ($lhs, $rhs) = (self, rhs.0);
+                $rem
+            }
+        }
+
+        impl DivAssign<Divisor<$int>> for $int {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: Divisor<$int>) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl RemAssign<Divisor<$int>> for $int {
+            #[inline(always)]
+            fn rem_assign(&mut self, rhs: Divisor<$int>) {
+                *self = *self % rhs;
+            }
+        }
+    };
+}
+
+macro_rules! unsigned_divisor {
+    ($($int:ty => $non_zero:ty),*) => {
+        $(
+            // `core` implements panic-free division by `NonZero*` for unsigned types.
+            divisor!($int, $non_zero, |_| true, |lhs, rhs| lhs / rhs, lhs % rhs);
+
+            impl From<$non_zero> for Divisor<$int> {
+                #[inline(always)]
+                fn from(value: $non_zero) -> Self {
+                    Divisor(value)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! signed_divisor {
+    ($($int:ty => $non_zero:ty),*) => {
+        $(
+            // The divisor is neither `0` nor `-1`, so wrapping operations are the same as ordinary
+            // ones, they just don't contain the overflow check.
+            divisor!($int, $non_zero, |value| value != -1, |lhs, rhs| lhs.wrapping_div(rhs.get()), lhs.wrapping_rem(rhs.get()));
+        )*
+    };
+}
+
+unsigned_divisor!(u8 => NonZeroU8, u16 => NonZeroU16, u32 => NonZeroU32, u64 => NonZeroU64, u128 => NonZeroU128, usize => NonZeroUsize);
+signed_divisor!(i8 => NonZeroI8, i16 => NonZeroI16, i32 => NonZeroI32, i64 => NonZeroI64, i128 => NonZeroI128, isize => NonZeroIsize);
+
+/// Failure of an operation of `Arith`, carrying the message `panic!()` would use.
+///
+/// The variant tells `dp_arith!()` which category the failure belongs to.
+#[doc(hidden)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ArithError {
+    Overflow(&'static str),
+    DivByZero(&'static str),
+}
+
+impl ArithError {
+    /// The message `panic!()` would use.
+    #[inline(always)]
+    pub fn message(self) -> &'static str {
+        match self {
+            ArithError::Overflow(message) | ArithError::DivByZero(message) => message,
+        }
+    }
+}
+
+/// Checked operations used by `dp_arith!()`, returning the kind of the failure along with the
+/// message `panic!()` would use.
+#[doc(hidden)]
+pub trait Arith: Sized {
+    fn checked_add(self, rhs: Self) -> Result<Self, ArithError>;
+    fn checked_sub(self, rhs: Self) -> Result<Self, ArithError>;
+    fn checked_mul(self, rhs: Self) -> Result<Self, ArithError>;
+    fn checked_div(self, rhs: Self) -> Result<Self, ArithError>;
+    fn checked_rem(self, rhs: Self) -> Result<Self, ArithError>;
+    fn checked_shl<R: Arith>(self, rhs: R) -> Result<Self, ArithError>;
+    fn checked_shr<R: Arith>(self, rhs: R) -> Result<Self, ArithError>;
+    /// Converts the right hand side of a shift, which can be any integer type.
+    fn shift_amount(self) -> Option<u32>;
+}
+
+/// Checked negation used by `dp_arith!()`, implemented for signed types only.
+#[doc(hidden)]
+pub trait ArithNeg: Sized {
+    fn checked_neg(self) -> Result<Self, ArithError>;
+}
+
+macro_rules! arith {
+    ($($int:ty),*) => {
+        $(
+            impl Arith for $int {
+                #[inline(always)]
+                fn checked_add(self, rhs: Self) -> Result<Self, ArithError> {
+                    self.checked_add(rhs).ok_or(ArithError::Overflow("attempt to add with overflow"))
+                }
+
+                #[inline(always)]
+                fn checked_sub(self, rhs: Self) -> Result<Self, ArithError> {
+                    self.checked_sub(rhs).ok_or(ArithError::Overflow("attempt to subtract with overflow"))
+                }
+
+                #[inline(always)]
+                fn checked_mul(self, rhs: Self) -> Result<Self, ArithError> {
+                    self.checked_mul(rhs).ok_or(ArithError::Overflow("attempt to multiply with overflow"))
+                }
+
+                #[inline(always)]
+                fn checked_div(self, rhs: Self) -> Result<Self, ArithError> {
+                    if rhs == 0 {
+                        return Err(ArithError::DivByZero("attempt to divide by zero"));
+                    }
+                    self.checked_div(rhs).ok_or(ArithError::Overflow("attempt to divide with overflow"))
+                }
+
+                #[inline(always)]
+                fn checked_rem(self, rhs: Self) -> Result<Self, ArithError> {
+                    if rhs == 0 {
+                        return Err(ArithError::DivByZero("attempt to calculate the remainder with a divisor of zero"));
+                    }
+                    self.checked_rem(rhs).ok_or(ArithError::Overflow("attempt to calculate the remainder with overflow"))
+                }
+
+                #[inline(always)]
+                fn checked_shl<R: Arith>(self, rhs: R) -> Result<Self, ArithError> {
+                    rhs.shift_amount().and_then(|rhs| self.checked_shl(rhs)).ok_or(ArithError::Overflow("attempt to shift left with overflow"))
+                }
+
+                #[inline(always)]
+                fn checked_shr<R: Arith>(self, rhs: R) -> Result<Self, ArithError> {
+                    rhs.shift_amount().and_then(|rhs| self.checked_shr(rhs)).ok_or(ArithError::Overflow("attempt to shift right with overflow"))
+                }
+
+                #[inline(always)]
+                fn shift_amount(self) -> Option<u32> {
+                    u32::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! arith_neg {
+    ($($int:ty),*) => {
+        $(
+            impl ArithNeg for $int {
+                #[inline(always)]
+                fn checked_neg(self) -> Result<Self, ArithError> {
+                    self.checked_neg().ok_or(ArithError::Overflow("attempt to negate with overflow"))
+                }
+            }
+        )*
+    };
+}
+
+arith!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+arith_neg!(i8, i16, i32, i64, i128, isize);
+
+dp_int!(DpU8, u8);
+dp_int!(DpU16, u16);
+dp_int!(DpU32, u32);
+dp_int!(DpU64, u64);
+dp_int!(DpU128, u128);
+dp_int!(DpUsize, usize);
+dp_signed!(DpI8, i8);
+dp_signed!(DpI16, i16);
+dp_signed!(DpI32, i32);
+dp_signed!(DpI64, i64);
+dp_signed!(DpI128, i128);
+dp_signed!(DpIsize, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::{Divisor, DpI64, DpU32, DpU8, DpUsize};
+
+    #[test]
+    fn arithmetic() {
+        let mut x = DpU32(6);
+        x *= DpU32(9);
+        x -= DpU32(12);
+        assert_eq!(x, DpU32(42));
+        assert_eq!(x / DpU32(4), DpU32(10));
+        assert_eq!(x % DpU32(4), DpU32(2));
+        assert_eq!(x << 1, DpU32(84));
+        assert_eq!(x >> 1, DpU32(21));
+        assert_eq!(-DpI64(42), DpI64(-42));
+        assert_eq!(DpU8::MAX.get(), 255);
+
+        let arr = [1, 2, 3];
+        let len = DpUsize(arr.len());
+        let middle = (len - DpUsize(1)) / DpUsize(2);
+        assert_eq!(arr[middle.get()], 2);
+    }
+
+    #[test]
+    fn divisor() {
+        use core::hint::black_box;
+
+        assert_eq!(Divisor::new(0u32), None);
+        assert_eq!(Divisor::new(0i32), None);
+        assert_eq!(Divisor::new(-1i32), None);
+
+        let divisor = Divisor::new(black_box(7u32)).unwrap();
+        assert_eq!(::call(|| black_box(45u32) / divisor), 6);
+        assert_eq!(::call(|| black_box(45u32) % divisor), 3);
+
+        let divisor = Divisor::new(black_box(-7i32)).unwrap();
+        assert_eq!(::call(|| black_box(i32::MIN) / divisor), i32::MIN / -7);
+        assert_eq!(::call(|| black_box(i32::MIN) % divisor), i32::MIN % -7);
+
+        let mut x = DpI64(-45);
+        x /= Divisor::dp_new(7);
+        assert_eq!(x, DpI64(-6));
+        assert_eq!(divisor.get(), -7);
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "invalid divisor")]
+    fn invalid_divisor() {
+        Divisor::dp_new(::core::hint::black_box(-1i64));
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "attempt to add with overflow")]
+    fn overflow() {
+        let _ = ::core::hint::black_box(DpU8::MAX) + DpU8(1);
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "attempt to divide with overflow")]
+    fn signed_division_overflow() {
+        let _ = ::core::hint::black_box(DpI64::MIN) / DpI64(-1);
+    }
+
+    #[cfg(dont_panic_impl = "panic")]
+    #[test]
+    #[should_panic(expected = "attempt to shift left with overflow")]
+    fn shift_overflow() {
+        let _ = DpU32(1) << ::core::hint::black_box(32);
+    }
+}
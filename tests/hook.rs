@@ -0,0 +1,37 @@
+//! The hook is global, so it's tested in a separate binary, where it can't affect other tests.
+
+// The panic handler defined by the `handler` feature conflicts with `std`.
+#![cfg(all(dont_panic_impl = "panic", not(feature = "handler")))]
+
+#[macro_use]
+extern crate dont_panic;
+
+use dont_panic::hook::{self, SiteInfo};
+use std::hint::black_box;
+use std::panic;
+use std::sync::Mutex;
+
+static CALLS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn record(info: &SiteInfo) {
+    let call = format!("{}!() {} at {}:{}:{}: {}", info.macro_name(), info.id(), info.file(), info.line(), info.column(), info.message());
+    CALLS.lock().unwrap().push(call);
+}
+
+fn answer() {
+    let answer = black_box(42);
+    if answer == 42 {
+        dont_panic!("the answer is {}", answer);
+    }
+}
+
+#[test]
+fn hook() {
+    hook::set_hook(record);
+    assert!(panic::catch_unwind(answer).is_err());
+    hook::reset_hook();
+    assert!(panic::catch_unwind(answer).is_err());
+
+    let calls = CALLS.lock().unwrap();
+    assert_eq!(*calls, ["dont_panic!() hook$tests/hook.rs:24:9 at tests/hook.rs:24:9: the answer is 42"]);
+}